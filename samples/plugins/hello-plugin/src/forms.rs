@@ -0,0 +1,516 @@
+//! Form schema builders for `require_input` responses
+//!
+//! The executor renders the schema as a form and calls the plugin again with
+//! the submitted values in `input`. The JSON produced here mirrors the
+//! executor's `DynamicFormSchema` model.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+mod phone;
+mod validation;
+mod wizard;
+
+pub use phone::{calling_code, phone_e164, to_e164};
+pub use validation::Rule;
+pub use wizard::{BackNavigation, Wizard, WizardState};
+
+/// A form shown to the user by a `require_input` response
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FormSchema {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_message: Option<String>,
+    fields: Vec<FormField>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    submit_button_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cancel_button_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    branding: Option<Branding>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    wizard: Option<wizard::WizardSchema>,
+}
+
+impl FormSchema {
+    pub fn new(title: &str) -> Self {
+        Self {
+            title: Some(title.to_string()),
+            ..Default::default()
+        }
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    /// Form-level error, e.g. when re-showing the form after a failed submission
+    pub fn error_message(mut self, message: &str) -> Self {
+        self.error_message = Some(message.to_string());
+        self
+    }
+
+    pub fn field(mut self, field: FormField) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    pub fn submit_button_text(mut self, text: &str) -> Self {
+        self.submit_button_text = Some(text.to_string());
+        self
+    }
+
+    /// Shows a cancel button with the given text
+    pub fn cancel_button_text(mut self, text: &str) -> Self {
+        self.cancel_button_text = Some(text.to_string());
+        self
+    }
+
+    /// Theming hints for the form screen. The executor applies only what the
+    /// tenant's plugin branding allows and ignores the rest.
+    pub fn branding(mut self, branding: Branding) -> Self {
+        self.branding = Some(branding);
+        self
+    }
+
+    /// Converts the schema into the `data` of a `require_input` output
+    pub fn into_data(self) -> HashMap<String, serde_json::Value> {
+        match serde_json::to_value(self) {
+            Ok(serde_json::Value::Object(map)) => map.into_iter().collect(),
+            _ => HashMap::new(),
+        }
+    }
+}
+
+/// Theming hints of a form screen
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Branding {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    logo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accent_color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    layout: Option<String>,
+}
+
+impl Branding {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Logo by the reference the tenant registered it under, not a URL
+    pub fn logo(mut self, reference: &str) -> Self {
+        self.logo = Some(reference.to_string());
+        self
+    }
+
+    /// Accent color as `#rrggbb`; must be one of the tenant's allowed colors
+    pub fn accent_color(mut self, color: &str) -> Self {
+        self.accent_color = Some(color.to_string());
+        self
+    }
+
+    /// Layout width: `narrow`, `medium`, `wide` or `full`
+    pub fn layout(mut self, layout: &str) -> Self {
+        self.layout = Some(layout.to_string());
+        self
+    }
+}
+
+/// A single input in a form
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FormField {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    name: String,
+    #[serde(rename = "type")]
+    field_type: String,
+    label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    placeholder: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+    required: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aria_label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aria_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    autocomplete: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_mode: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options: Option<Vec<FormOption>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rows: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    show_if: Option<Condition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    accept: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_size: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    options_function: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variant: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    href: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    min_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_date: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    countries: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    validation: Vec<Rule>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validation_error: Option<String>,
+}
+
+impl FormField {
+    fn new(name: &str, field_type: &str, label: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            field_type: field_type.to_string(),
+            label: label.to_string(),
+            placeholder: None,
+            value: None,
+            required: false,
+            help_text: None,
+            aria_label: None,
+            aria_description: None,
+            autocomplete: None,
+            input_mode: None,
+            options: None,
+            rows: None,
+            show_if: None,
+            accept: None,
+            max_size: None,
+            options_function: None,
+            content: None,
+            variant: None,
+            href: None,
+            min_date: None,
+            max_date: None,
+            default_country: None,
+            countries: None,
+            validation: Vec::new(),
+            validation_error: None,
+        }
+    }
+
+    pub fn text(name: &str, label: &str) -> Self {
+        Self::new(name, "text", label)
+    }
+
+    pub fn email(name: &str, label: &str) -> Self {
+        Self::new(name, "email", label)
+    }
+
+    pub fn number(name: &str, label: &str) -> Self {
+        Self::new(name, "number", label)
+    }
+
+    pub fn textarea(name: &str, label: &str, rows: u32) -> Self {
+        Self {
+            rows: Some(rows),
+            ..Self::new(name, "textarea", label)
+        }
+    }
+
+    pub fn select(name: &str, label: &str) -> Self {
+        Self::new(name, "select", label)
+    }
+
+    pub fn checkbox(name: &str, label: &str) -> Self {
+        Self::new(name, "checkbox", label)
+    }
+
+    pub fn hidden(name: &str, value: &str) -> Self {
+        Self {
+            value: Some(value.to_string()),
+            ..Self::new(name, "hidden", "")
+        }
+    }
+
+    /// Masked password input.
+    ///
+    /// Password, OTP and masked text values are sensitive: the executor passes
+    /// them to this plugin but never logs them, echoes them back into the
+    /// form, or stores them in journey submissions.
+    pub fn password(name: &str, label: &str) -> Self {
+        Self::new(name, "password", label)
+    }
+
+    /// One-time code input (numeric keypad, autofill from SMS where supported)
+    pub fn otp(name: &str, label: &str) -> Self {
+        Self::new(name, "otp", label)
+    }
+
+    /// Free text that is masked while typed, e.g. a national ID number
+    pub fn masked_text(name: &str, label: &str) -> Self {
+        Self::new(name, "masked_text", label)
+    }
+
+    /// File upload. The executor stores the file and the plugin receives an
+    /// attachment handle (`id`, `fileName`, `contentType`, `size`) whose bytes
+    /// are read through the `read_attachment` host function.
+    pub fn file(name: &str, label: &str) -> Self {
+        Self::new(name, "file", label)
+    }
+
+    /// Date picker. The value is submitted as `YYYY-MM-DD`.
+    pub fn date(name: &str, label: &str) -> Self {
+        Self::new(name, "date", label)
+    }
+
+    /// Phone number input with a country selector. The selected country is
+    /// submitted in `<name>_country`; [`phone_e164`] combines both into an
+    /// E.164 number.
+    pub fn phone(name: &str, label: &str) -> Self {
+        Self::new(name, "phone", label)
+    }
+
+    /// Country selector. The value is an ISO 3166-1 alpha-2 code, e.g. `GB`.
+    pub fn country(name: &str, label: &str) -> Self {
+        Self::new(name, "country", label)
+    }
+
+    /// Markdown text shown between inputs, e.g. instructions or legal text.
+    ///
+    /// Content blocks submit nothing. The executor renders a safe subset of
+    /// markdown (paragraphs, `#` headings, lists, `**bold**`, `*italic*`,
+    /// `` `code` `` and links) and shows anything else, including HTML, as
+    /// text. Links must be absolute `https`, `http`, `mailto`, `tel` or
+    /// `otpauth` URLs.
+    pub fn markdown(text: &str) -> Self {
+        Self {
+            content: Some(text.to_string()),
+            ..Self::new("", "markdown", "")
+        }
+    }
+
+    /// Highlighted markdown message; `variant` is `info`, `success`,
+    /// `warning` or `danger`
+    pub fn alert(variant: &str, text: &str) -> Self {
+        Self {
+            content: Some(text.to_string()),
+            variant: Some(variant.to_string()),
+            ..Self::new("", "alert", "")
+        }
+    }
+
+    /// Link opened in a new tab, e.g. terms of service or an `otpauth://`
+    /// URL that provisions an authenticator app on a phone
+    pub fn link(label: &str, href: &str) -> Self {
+        Self {
+            href: Some(href.to_string()),
+            ..Self::new("", "link", label)
+        }
+    }
+
+    pub fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    /// Initial value. Ignored for sensitive field types.
+    pub fn value(mut self, value: &str) -> Self {
+        self.value = Some(value.to_string());
+        self
+    }
+
+    pub fn help_text(mut self, help_text: &str) -> Self {
+        self.help_text = Some(help_text.to_string());
+        self
+    }
+
+    /// Accessible name announced by screen readers instead of the visible
+    /// label, e.g. when the label alone is ambiguous
+    pub fn aria_label(mut self, label: &str) -> Self {
+        self.aria_label = Some(label.to_string());
+        self
+    }
+
+    /// Extra description announced by screen readers but not shown
+    pub fn aria_description(mut self, description: &str) -> Self {
+        self.aria_description = Some(description.to_string());
+        self
+    }
+
+    /// HTML autocomplete hint, e.g. `given-name`, `bday` or
+    /// `shipping postal-code`, so browsers and password managers can fill the
+    /// field. Overrides the default of the field type.
+    pub fn autocomplete(mut self, hint: &str) -> Self {
+        self.autocomplete = Some(hint.to_string());
+        self
+    }
+
+    /// Virtual keyboard to show: `none`, `text`, `decimal`, `numeric`, `tel`,
+    /// `search`, `email` or `url`. Overrides the default of the field type.
+    pub fn input_mode(mut self, mode: &str) -> Self {
+        self.input_mode = Some(mode.to_string());
+        self
+    }
+
+    /// Shows the field only while the condition holds.
+    ///
+    /// The condition must reference another field of the same form; the
+    /// executor rejects the form otherwise. Hidden fields are not submitted.
+    pub fn show_if(mut self, condition: Condition) -> Self {
+        self.show_if = Some(condition);
+        self
+    }
+
+    /// Adds a validation rule, enforced in the browser before submission and
+    /// by [`FormSchema::validate`] on the submitted input
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.validation.push(rule);
+        self
+    }
+
+    /// Loads a select field's options on demand from the exported plugin
+    /// function `function`, instead of listing them upfront.
+    ///
+    /// The function is called with the partial form state in `input` (the
+    /// search text is in `__query`) and returns [`options_data`].
+    pub fn options_function(mut self, function: &str) -> Self {
+        self.options_function = Some(function.to_string());
+        self
+    }
+
+    /// Earliest date accepted by a date field, as `YYYY-MM-DD`
+    pub fn min_date(mut self, date: &str) -> Self {
+        self.min_date = Some(date.to_string());
+        self
+    }
+
+    /// Latest date accepted by a date field, as `YYYY-MM-DD`
+    pub fn max_date(mut self, date: &str) -> Self {
+        self.max_date = Some(date.to_string());
+        self
+    }
+
+    /// Country preselected in a phone or country field (ISO 3166-1 alpha-2)
+    pub fn default_country(mut self, country: &str) -> Self {
+        self.default_country = Some(country.to_string());
+        self
+    }
+
+    /// Restricts a phone or country field to the given countries
+    /// (ISO 3166-1 alpha-2)
+    pub fn countries(mut self, countries: &[&str]) -> Self {
+        self.countries = Some(countries.iter().map(|c| c.to_string()).collect());
+        self
+    }
+
+    /// Accepted content types of a file field, e.g. `application/pdf,image/*`
+    pub fn accept(mut self, content_types: &str) -> Self {
+        self.accept = Some(content_types.to_string());
+        self
+    }
+
+    /// Largest file accepted by a file field, in bytes; larger uploads are rejected by the host
+    pub fn max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Adds an option to a select or radio field
+    pub fn option(mut self, value: &str, label: &str) -> Self {
+        self.options
+            .get_or_insert_with(Vec::new)
+            .push(FormOption::new(value, label));
+        self
+    }
+}
+
+/// Option of a select or radio field
+#[derive(Serialize)]
+pub struct FormOption {
+    value: String,
+    label: String,
+}
+
+impl FormOption {
+    pub fn new(value: &str, label: &str) -> Self {
+        Self {
+            value: value.to_string(),
+            label: label.to_string(),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+}
+
+/// Converts the result of an options function into the `data` of its output
+pub fn options_data(options: Vec<FormOption>) -> HashMap<String, serde_json::Value> {
+    let mut data = HashMap::new();
+    data.insert("options".to_string(), serde_json::json!(options));
+    data
+}
+
+/// Visibility condition on another field's value
+#[derive(Serialize)]
+pub struct Condition {
+    field: String,
+    operator: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<String>,
+}
+
+impl Condition {
+    fn new(field: &str, operator: &'static str, value: Option<&str>) -> Self {
+        Self {
+            field: field.to_string(),
+            operator,
+            value: value.map(str::to_string),
+        }
+    }
+
+    pub fn equals(field: &str, value: &str) -> Self {
+        Self::new(field, "equals", Some(value))
+    }
+
+    pub fn not_equals(field: &str, value: &str) -> Self {
+        Self::new(field, "not_equals", Some(value))
+    }
+
+    pub fn is_empty(field: &str) -> Self {
+        Self::new(field, "empty", None)
+    }
+
+    pub fn is_not_empty(field: &str) -> Self {
+        Self::new(field, "not_empty", None)
+    }
+
+    /// Evaluates the condition against submitted input, as the browser does
+    fn is_met(&self, input: &HashMap<String, serde_json::Value>) -> bool {
+        let actual = validation::field_value(input, &self.field);
+        match self.operator {
+            "not_equals" => Some(actual.as_ref()) != self.value.as_deref(),
+            "empty" => actual.is_empty(),
+            "not_empty" => !actual.is_empty(),
+            _ => Some(actual.as_ref()) == self.value.as_deref(),
+        }
+    }
+}
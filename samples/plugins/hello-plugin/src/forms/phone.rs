@@ -0,0 +1,199 @@
+//! Phone number normalization for `phone` fields
+//!
+//! A phone field is submitted as the number the user typed plus the selected
+//! country in `<field>_country`. [`phone_e164`] combines the two into an
+//! E.164 number (`+447911123456`) that can be stored or sent to an SMS
+//! provider.
+
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Suffix of the companion input holding a phone field's selected country
+pub const COUNTRY_SUFFIX: &str = "_country";
+
+/// Countries that keep the leading zero of national numbers after the
+/// calling code (no trunk prefix to strip)
+const KEEPS_LEADING_ZERO: &[&str] = &["IT", "SM", "VA", "CI", "GA", "CG", "TG"];
+
+/// ITU-T E.164 calling codes by ISO 3166-1 alpha-2 country code
+#[rustfmt::skip]
+const CALLING_CODES: &[(&str, &str)] = &[
+    ("AD", "376"), ("AE", "971"), ("AF", "93"), ("AG", "1"), ("AI", "1"),
+    ("AL", "355"), ("AM", "374"), ("AO", "244"), ("AR", "54"), ("AS", "1"),
+    ("AT", "43"), ("AU", "61"), ("AW", "297"), ("AX", "358"), ("AZ", "994"),
+    ("BA", "387"), ("BB", "1"), ("BD", "880"), ("BE", "32"), ("BF", "226"),
+    ("BG", "359"), ("BH", "973"), ("BI", "257"), ("BJ", "229"), ("BL", "590"),
+    ("BM", "1"), ("BN", "673"), ("BO", "591"), ("BQ", "599"), ("BR", "55"),
+    ("BS", "1"), ("BT", "975"), ("BW", "267"), ("BY", "375"), ("BZ", "501"),
+    ("CA", "1"), ("CC", "61"), ("CD", "243"), ("CF", "236"), ("CG", "242"),
+    ("CH", "41"), ("CI", "225"), ("CK", "682"), ("CL", "56"), ("CM", "237"),
+    ("CN", "86"), ("CO", "57"), ("CR", "506"), ("CU", "53"), ("CV", "238"),
+    ("CW", "599"), ("CX", "61"), ("CY", "357"), ("CZ", "420"), ("DE", "49"),
+    ("DJ", "253"), ("DK", "45"), ("DM", "1"), ("DO", "1"), ("DZ", "213"),
+    ("EC", "593"), ("EE", "372"), ("EG", "20"), ("EH", "212"), ("ER", "291"),
+    ("ES", "34"), ("ET", "251"), ("FI", "358"), ("FJ", "679"), ("FK", "500"),
+    ("FM", "691"), ("FO", "298"), ("FR", "33"), ("GA", "241"), ("GB", "44"),
+    ("GD", "1"), ("GE", "995"), ("GF", "594"), ("GG", "44"), ("GH", "233"),
+    ("GI", "350"), ("GL", "299"), ("GM", "220"), ("GN", "224"), ("GP", "590"),
+    ("GQ", "240"), ("GR", "30"), ("GT", "502"), ("GU", "1"), ("GW", "245"),
+    ("GY", "592"), ("HK", "852"), ("HN", "504"), ("HR", "385"), ("HT", "509"),
+    ("HU", "36"), ("ID", "62"), ("IE", "353"), ("IL", "972"), ("IM", "44"),
+    ("IN", "91"), ("IO", "246"), ("IQ", "964"), ("IR", "98"), ("IS", "354"),
+    ("IT", "39"), ("JE", "44"), ("JM", "1"), ("JO", "962"), ("JP", "81"),
+    ("KE", "254"), ("KG", "996"), ("KH", "855"), ("KI", "686"), ("KM", "269"),
+    ("KN", "1"), ("KP", "850"), ("KR", "82"), ("KW", "965"), ("KY", "1"),
+    ("KZ", "7"), ("LA", "856"), ("LB", "961"), ("LC", "1"), ("LI", "423"),
+    ("LK", "94"), ("LR", "231"), ("LS", "266"), ("LT", "370"), ("LU", "352"),
+    ("LV", "371"), ("LY", "218"), ("MA", "212"), ("MC", "377"), ("MD", "373"),
+    ("ME", "382"), ("MF", "590"), ("MG", "261"), ("MH", "692"), ("MK", "389"),
+    ("ML", "223"), ("MM", "95"), ("MN", "976"), ("MO", "853"), ("MP", "1"),
+    ("MQ", "596"), ("MR", "222"), ("MS", "1"), ("MT", "356"), ("MU", "230"),
+    ("MV", "960"), ("MW", "265"), ("MX", "52"), ("MY", "60"), ("MZ", "258"),
+    ("NA", "264"), ("NC", "687"), ("NE", "227"), ("NF", "672"), ("NG", "234"),
+    ("NI", "505"), ("NL", "31"), ("NO", "47"), ("NP", "977"), ("NR", "674"),
+    ("NU", "683"), ("NZ", "64"), ("OM", "968"), ("PA", "507"), ("PE", "51"),
+    ("PF", "689"), ("PG", "675"), ("PH", "63"), ("PK", "92"), ("PL", "48"),
+    ("PM", "508"), ("PR", "1"), ("PS", "970"), ("PT", "351"), ("PW", "680"),
+    ("PY", "595"), ("QA", "974"), ("RE", "262"), ("RO", "40"), ("RS", "381"),
+    ("RU", "7"), ("RW", "250"), ("SA", "966"), ("SB", "677"), ("SC", "248"),
+    ("SD", "249"), ("SE", "46"), ("SG", "65"), ("SH", "290"), ("SI", "386"),
+    ("SJ", "47"), ("SK", "421"), ("SL", "232"), ("SM", "378"), ("SN", "221"),
+    ("SO", "252"), ("SR", "597"), ("SS", "211"), ("ST", "239"), ("SV", "503"),
+    ("SX", "1"), ("SY", "963"), ("SZ", "268"), ("TC", "1"), ("TD", "235"),
+    ("TG", "228"), ("TH", "66"), ("TJ", "992"), ("TK", "690"), ("TL", "670"),
+    ("TM", "993"), ("TN", "216"), ("TO", "676"), ("TR", "90"), ("TT", "1"),
+    ("TV", "688"), ("TW", "886"), ("TZ", "255"), ("UA", "380"), ("UG", "256"),
+    ("US", "1"), ("UY", "598"), ("UZ", "998"), ("VA", "39"), ("VC", "1"),
+    ("VE", "58"), ("VG", "1"), ("VI", "1"), ("VN", "84"), ("VU", "678"),
+    ("WF", "681"), ("WS", "685"), ("XK", "383"), ("YE", "967"), ("YT", "262"),
+    ("ZA", "27"), ("ZM", "260"), ("ZW", "263"),
+];
+
+/// Calling code of a country, e.g. `44` for `GB`
+pub fn calling_code(country: &str) -> Option<&'static str> {
+    CALLING_CODES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(country))
+        .map(|(_, calling_code)| *calling_code)
+}
+
+/// Normalizes a phone number to E.164.
+///
+/// Numbers starting with `+` or `00` are taken as international and
+/// `country` is ignored. Other numbers are national numbers of `country`
+/// (ISO 3166-1 alpha-2) and lose their trunk prefix `0`. Spaces, dashes,
+/// dots and parentheses are ignored. Returns `None` if the number has other
+/// characters, the country is unknown, or the result is not 7 to 15 digits.
+pub fn to_e164(number: &str, country: Option<&str>) -> Option<String> {
+    let number = number.trim();
+    let (international, rest) = match number.strip_prefix('+') {
+        Some(rest) => (true, rest),
+        None => match number.strip_prefix("00") {
+            Some(rest) => (true, rest),
+            None => (false, number),
+        },
+    };
+
+    let mut digits = String::with_capacity(rest.len());
+    for c in rest.chars() {
+        match c {
+            '0'..='9' => digits.push(c),
+            ' ' | '-' | '.' | '(' | ')' => {}
+            _ => return None,
+        }
+    }
+
+    if !international {
+        let country = country?;
+        let code = calling_code(country)?;
+        let national = if KEEPS_LEADING_ZERO
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(country))
+        {
+            digits.as_str()
+        } else {
+            digits.strip_prefix('0').unwrap_or(&digits)
+        };
+        digits = format!("{}{}", code, national);
+    }
+
+    if digits.starts_with('0') || !(7..=15).contains(&digits.len()) {
+        return None;
+    }
+
+    Some(format!("+{}", digits))
+}
+
+/// E.164 number submitted in the phone field `field`, using the country
+/// selected next to it. `None` if the field is empty or invalid.
+pub fn phone_e164(input: &HashMap<String, Value>, field: &str) -> Option<String> {
+    let number = super::validation::field_value(input, field);
+    if number.trim().is_empty() {
+        return None;
+    }
+
+    let country = super::validation::field_value(input, &format!("{}{}", field, COUNTRY_SUFFIX));
+    to_e164(&number, Some(country.as_ref()).filter(|c| !c.is_empty()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn to_e164_strips_the_trunk_prefix_of_national_numbers() {
+        assert_eq!(
+            to_e164("07911 123456", Some("GB")).as_deref(),
+            Some("+447911123456")
+        );
+        assert_eq!(
+            to_e164("(202) 555-0143", Some("us")).as_deref(),
+            Some("+12025550143")
+        );
+    }
+
+    #[test]
+    fn to_e164_keeps_the_leading_zero_where_the_country_dials_it() {
+        assert_eq!(
+            to_e164("06 1234 5678", Some("IT")).as_deref(),
+            Some("+390612345678")
+        );
+    }
+
+    #[test]
+    fn to_e164_ignores_the_country_of_international_numbers() {
+        assert_eq!(
+            to_e164("+44 7911 123456", Some("US")).as_deref(),
+            Some("+447911123456")
+        );
+        assert_eq!(
+            to_e164("0044 7911 123456", None).as_deref(),
+            Some("+447911123456")
+        );
+    }
+
+    #[test]
+    fn to_e164_rejects_invalid_numbers() {
+        assert_eq!(to_e164("07911 123456", None), None);
+        assert_eq!(to_e164("07911 123456", Some("ZZ")), None);
+        assert_eq!(to_e164("07911 12345x", Some("GB")), None);
+        assert_eq!(to_e164("+0 7911 123456", None), None);
+        assert_eq!(to_e164("+44 12", None), None);
+        assert_eq!(to_e164("+44 7911 1234 5678 90", None), None);
+    }
+
+    #[test]
+    fn phone_e164_uses_the_selected_country() {
+        let input = HashMap::from([
+            ("mobile".to_string(), json!("07911 123456")),
+            ("mobile_country".to_string(), json!("GB")),
+        ]);
+
+        assert_eq!(
+            phone_e164(&input, "mobile").as_deref(),
+            Some("+447911123456")
+        );
+        assert_eq!(phone_e164(&input, "landline"), None);
+    }
+}
@@ -0,0 +1,421 @@
+//! Declarative validation rules for form fields
+//!
+//! Rules are serialized into the form schema so the browser can check them
+//! before submitting, and [`FormSchema::validate`] applies the same rules to
+//! the submitted input, so the client-side and server-side checks never drift.
+
+use super::{phone, Condition, FormField, FormSchema};
+use regex::Regex;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// A validation rule attached to a field with [`FormField::rule`](super::FormField::rule)
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Rule {
+    /// The whole value must match the regular expression. Use syntax shared
+    /// by Rust and JavaScript regexes (no lookaround or backreferences).
+    Pattern {
+        value: String,
+        message: String,
+    },
+    MinLength {
+        value: usize,
+        message: String,
+    },
+    MaxLength {
+        value: usize,
+        message: String,
+    },
+    Min {
+        value: f64,
+        message: String,
+    },
+    Max {
+        value: f64,
+        message: String,
+    },
+    /// The field is required while the condition holds
+    RequiredIf {
+        condition: Condition,
+        message: String,
+    },
+}
+
+impl Rule {
+    pub fn pattern(regex: &str, message: &str) -> Self {
+        Rule::Pattern {
+            value: regex.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn min_length(length: usize) -> Self {
+        Rule::MinLength {
+            value: length,
+            message: format!("Must be at least {} characters", length),
+        }
+    }
+
+    pub fn max_length(length: usize) -> Self {
+        Rule::MaxLength {
+            value: length,
+            message: format!("Must be at most {} characters", length),
+        }
+    }
+
+    pub fn min(value: f64) -> Self {
+        Rule::Min {
+            value,
+            message: format!("Must be at least {}", value),
+        }
+    }
+
+    pub fn max(value: f64) -> Self {
+        Rule::Max {
+            value,
+            message: format!("Must be at most {}", value),
+        }
+    }
+
+    pub fn required_if(condition: Condition) -> Self {
+        Rule::RequiredIf {
+            condition,
+            message: "This field is required".to_string(),
+        }
+    }
+
+    /// Replaces the rule's error message
+    pub fn message(mut self, text: &str) -> Self {
+        match &mut self {
+            Rule::Pattern { message, .. }
+            | Rule::MinLength { message, .. }
+            | Rule::MaxLength { message, .. }
+            | Rule::Min { message, .. }
+            | Rule::Max { message, .. }
+            | Rule::RequiredIf { message, .. } => *message = text.to_string(),
+        }
+        self
+    }
+
+    /// Returns the error message if `value` breaks the rule. Empty values
+    /// only fail `RequiredIf`; other rules apply once something was entered.
+    fn check(&self, value: &str, input: &HashMap<String, Value>) -> Option<&str> {
+        let failed = match self {
+            Rule::RequiredIf { condition, .. } => {
+                condition.is_met(input) && value.trim().is_empty()
+            }
+            _ if value.is_empty() => false,
+            // Anchored like the HTML pattern attribute. An invalid regex is
+            // skipped here, matching the browser.
+            Rule::Pattern { value: pattern, .. } => Regex::new(&format!("^(?:{})$", pattern))
+                .map(|regex| !regex.is_match(value))
+                .unwrap_or(false),
+            Rule::MinLength { value: min, .. } => value.chars().count() < *min,
+            Rule::MaxLength { value: max, .. } => value.chars().count() > *max,
+            Rule::Min { value: min, .. } => value.parse::<f64>().is_ok_and(|n| n < *min),
+            Rule::Max { value: max, .. } => value.parse::<f64>().is_ok_and(|n| n > *max),
+        };
+
+        if failed {
+            Some(self.error_message())
+        } else {
+            None
+        }
+    }
+
+    fn error_message(&self) -> &str {
+        match self {
+            Rule::Pattern { message, .. }
+            | Rule::MinLength { message, .. }
+            | Rule::MaxLength { message, .. }
+            | Rule::Min { message, .. }
+            | Rule::Max { message, .. }
+            | Rule::RequiredIf { message, .. } => message,
+        }
+    }
+}
+
+/// Submitted value of a field as text, as the browser sent it
+pub(super) fn field_value<'a>(
+    input: &'a HashMap<String, Value>,
+    name: &str,
+) -> std::borrow::Cow<'a, str> {
+    match input.get(name) {
+        Some(Value::String(s)) => s.as_str().into(),
+        Some(Value::Null) | None => "".into(),
+        Some(other) => other.to_string().into(),
+    }
+}
+
+impl FormSchema {
+    /// Checks submitted `input` against the `required` flags and rules of
+    /// the fields, returning an error message per invalid field. Fields
+    /// hidden by `show_if` are skipped, as the browser does not submit them.
+    pub fn validate(&self, input: &HashMap<String, Value>) -> HashMap<String, String> {
+        let mut errors = HashMap::new();
+
+        for field in &self.fields {
+            if field.show_if.as_ref().is_some_and(|c| !c.is_met(input)) {
+                continue;
+            }
+
+            let value = field_value(input, &field.name);
+            if field.required && value.trim().is_empty() {
+                errors.insert(field.name.clone(), format!("{} is required", field.label));
+                continue;
+            }
+
+            if let Some(message) = field.type_error(&value, input) {
+                errors.insert(field.name.clone(), message);
+            } else if let Some(message) = field
+                .validation
+                .iter()
+                .find_map(|rule| rule.check(&value, input))
+            {
+                errors.insert(field.name.clone(), message.to_string());
+            }
+        }
+
+        errors
+    }
+
+    /// Marks fields with the errors returned by [`FormSchema::validate`], to
+    /// show the form again
+    pub fn with_errors(mut self, errors: &HashMap<String, String>) -> Self {
+        for field in &mut self.fields {
+            field.validation_error = errors.get(&field.name).cloned();
+        }
+        self
+    }
+}
+
+impl FormField {
+    /// Checks that a non-empty value is valid for the field type: a real
+    /// date within the field's range, or a country (and phone number) the
+    /// field accepts
+    fn type_error(&self, value: &str, input: &HashMap<String, Value>) -> Option<String> {
+        if value.is_empty() {
+            return None;
+        }
+
+        match self.field_type.as_str() {
+            "date" if !is_valid_date(value) => Some("Enter a valid date".to_string()),
+            "date" => match (self.min_date.as_deref(), self.max_date.as_deref()) {
+                (Some(min), _) if value < min => Some(format!("Must be on or after {}", min)),
+                (_, Some(max)) if value > max => Some(format!("Must be on or before {}", max)),
+                _ => None,
+            },
+            "country" => {
+                (!self.accepts_country(value)).then(|| "Select a valid country".to_string())
+            }
+            "phone" => {
+                let country =
+                    field_value(input, &format!("{}{}", self.name, phone::COUNTRY_SUFFIX));
+                let valid = (country.is_empty() || self.accepts_country(&country))
+                    && phone::phone_e164(input, &self.name).is_some();
+                (!valid).then(|| "Enter a valid phone number".to_string())
+            }
+            _ => None,
+        }
+    }
+
+    fn accepts_country(&self, country: &str) -> bool {
+        phone::calling_code(country).is_some()
+            && self
+                .countries
+                .as_ref()
+                .is_none_or(|countries| countries.iter().any(|c| c.eq_ignore_ascii_case(country)))
+    }
+}
+
+/// Whether `value` is a calendar date in `YYYY-MM-DD` form, as submitted by
+/// date inputs
+fn is_valid_date(value: &str) -> bool {
+    let mut parts = value.splitn(3, '-');
+    let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+    if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+        return false;
+    }
+    if ![year, month, day]
+        .iter()
+        .all(|part| part.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return false;
+    }
+    let (Ok(year), Ok(month), Ok(day)) = (
+        year.parse::<u32>(),
+        month.parse::<u32>(),
+        day.parse::<u32>(),
+    ) else {
+        return false;
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn input(values: &[(&str, &str)]) -> HashMap<String, Value> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), json!(value)))
+            .collect()
+    }
+
+    #[test]
+    fn validate_reports_missing_required_field() {
+        let form = FormSchema::new("Profile").field(FormField::text("name", "Name").required());
+
+        let errors = form.validate(&input(&[("name", "  ")]));
+
+        assert_eq!(
+            errors.get("name").map(String::as_str),
+            Some("Name is required")
+        );
+    }
+
+    #[test]
+    fn validate_anchors_pattern_to_whole_value() {
+        let form = FormSchema::new("Verify").field(
+            FormField::otp("code", "Code").rule(Rule::pattern("[0-9]{6}", "Enter the 6 digits")),
+        );
+
+        assert!(form.validate(&input(&[("code", "123456")])).is_empty());
+        assert_eq!(
+            form.validate(&input(&[("code", "1234567")]))
+                .get("code")
+                .map(String::as_str),
+            Some("Enter the 6 digits")
+        );
+    }
+
+    #[test]
+    fn validate_counts_characters_for_length_rules() {
+        let form = FormSchema::new("Profile").field(
+            FormField::text("nickname", "Nickname")
+                .rule(Rule::min_length(2))
+                .rule(Rule::max_length(4)),
+        );
+
+        assert!(form.validate(&input(&[("nickname", "éé")])).is_empty());
+        assert!(form
+            .validate(&input(&[("nickname", "a")]))
+            .contains_key("nickname"));
+        assert!(form
+            .validate(&input(&[("nickname", "abcde")]))
+            .contains_key("nickname"));
+    }
+
+    #[test]
+    fn validate_checks_numeric_bounds() {
+        let form = FormSchema::new("Order").field(
+            FormField::number("quantity", "Quantity")
+                .rule(Rule::min(1.0))
+                .rule(Rule::max(10.0).message("At most 10 per order")),
+        );
+
+        assert!(form.validate(&input(&[("quantity", "10")])).is_empty());
+        assert_eq!(
+            form.validate(&input(&[("quantity", "11")]))
+                .get("quantity")
+                .map(String::as_str),
+            Some("At most 10 per order")
+        );
+        assert!(form
+            .validate(&input(&[("quantity", "0")]))
+            .contains_key("quantity"));
+    }
+
+    #[test]
+    fn validate_skips_rules_for_empty_optional_value() {
+        let form = FormSchema::new("Profile")
+            .field(FormField::text("nickname", "Nickname").rule(Rule::min_length(2)));
+
+        assert!(form.validate(&input(&[])).is_empty());
+    }
+
+    #[test]
+    fn validate_applies_required_if_only_while_condition_holds() {
+        let form = FormSchema::new("Contact")
+            .field(FormField::select("contact", "Contact by"))
+            .field(
+                FormField::text("phone", "Phone")
+                    .rule(Rule::required_if(Condition::equals("contact", "phone"))),
+            );
+
+        assert!(form.validate(&input(&[("contact", "email")])).is_empty());
+        assert_eq!(
+            form.validate(&input(&[("contact", "phone")]))
+                .get("phone")
+                .map(String::as_str),
+            Some("This field is required")
+        );
+    }
+
+    #[test]
+    fn validate_skips_fields_hidden_by_show_if() {
+        let form = FormSchema::new("Contact")
+            .field(FormField::checkbox("newsletter", "Newsletter"))
+            .field(
+                FormField::email("email", "Email")
+                    .required()
+                    .show_if(Condition::equals("newsletter", "true")),
+            );
+
+        assert!(form.validate(&input(&[])).is_empty());
+        assert!(form
+            .validate(&input(&[("newsletter", "true")]))
+            .contains_key("email"));
+    }
+
+    #[test]
+    fn with_errors_marks_invalid_fields() {
+        let form = FormSchema::new("Profile").field(FormField::text("name", "Name").required());
+        let errors = form.validate(&input(&[]));
+
+        let data = form.with_errors(&errors).into_data();
+
+        assert_eq!(
+            data["fields"][0]["validationError"],
+            json!("Name is required")
+        );
+    }
+
+    #[test]
+    fn is_valid_date_checks_calendar_days() {
+        assert!(is_valid_date("2024-02-29"));
+        assert!(is_valid_date("2000-02-29"));
+        assert!(is_valid_date("2023-12-31"));
+
+        assert!(!is_valid_date("2023-02-29"));
+        assert!(!is_valid_date("1900-02-29"));
+        assert!(!is_valid_date("2023-04-31"));
+        assert!(!is_valid_date("2023-13-01"));
+        assert!(!is_valid_date("2023-00-10"));
+        assert!(!is_valid_date("2023-01-00"));
+    }
+
+    #[test]
+    fn is_valid_date_requires_yyyy_mm_dd() {
+        assert!(!is_valid_date("2023-1-01"));
+        assert!(!is_valid_date("23-01-01"));
+        assert!(!is_valid_date("01/02/2023"));
+        assert!(!is_valid_date("2023-01"));
+        assert!(!is_valid_date("2023-01-+1"));
+    }
+}
@@ -0,0 +1,158 @@
+//! Multi-page forms
+//!
+//! A [`Wizard`] splits a long form into pages that render as a stepper with a
+//! progress bar. Each `require_input` response shows one page. Between
+//! submissions the executor keeps the step's position in journey data: the
+//! page shown last, the pages submitted so far and their answers. Masked and
+//! sensitive fields are not kept. [`WizardState`] reads that position back,
+//! so the plugin itself stays stateless.
+
+use super::FormSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Journey data key holding the wizard state of each plugin step, keyed by step id
+pub const JOURNEY_DATA_KEY: &str = "pluginWizard";
+
+/// Whether the user can return to earlier pages
+#[derive(Serialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum BackNavigation {
+    /// A back button returns to the previous page without validating the current one
+    #[default]
+    Allowed,
+    /// No back button, e.g. once a page has triggered a side effect
+    Disabled,
+}
+
+#[derive(Serialize, Clone)]
+struct WizardPage {
+    id: String,
+    title: String,
+}
+
+/// The pages of a multi-page form, in order
+#[derive(Default)]
+pub struct Wizard {
+    pages: Vec<WizardPage>,
+    back_navigation: BackNavigation,
+}
+
+/// Wizard structure embedded in the schema of one page
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct WizardSchema {
+    pages: Vec<WizardPage>,
+    current_page: String,
+    progress: u8,
+    back_navigation: BackNavigation,
+}
+
+impl Wizard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a page; `id` identifies it in [`WizardState`], `title` labels it in the stepper
+    pub fn page(mut self, id: &str, title: &str) -> Self {
+        self.pages.push(WizardPage {
+            id: id.to_string(),
+            title: title.to_string(),
+        });
+        self
+    }
+
+    pub fn back_navigation(mut self, policy: BackNavigation) -> Self {
+        self.back_navigation = policy;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+
+    /// Position of the page with the given id
+    pub fn index_of(&self, id: &str) -> Option<usize> {
+        self.pages.iter().position(|p| p.id == id)
+    }
+
+    /// Page to show next: the first page initially, the previous page after
+    /// a back request, otherwise the page after the last one submitted.
+    /// Equals [`len`](Self::len) once the last page has been submitted.
+    pub fn position(&self, state: &WizardState) -> usize {
+        let last = state.history.last().and_then(|id| self.index_of(id));
+        match last {
+            Some(index) if state.back => index,
+            Some(index) => (index + 1).min(self.len()),
+            None => 0,
+        }
+    }
+
+    /// Page that was just submitted, if the plugin is called with a
+    /// submission (not a back request). Validate the input against this
+    /// page's form and show it again with errors if needed.
+    pub fn submitted_page(&self, state: &WizardState) -> Option<usize> {
+        if !state.submitted || state.back {
+            return None;
+        }
+        state.history.last().and_then(|id| self.index_of(id))
+    }
+
+    /// Wizard structure of the page at `index`; progress counts the pages before it
+    pub(super) fn schema(&self, index: usize) -> Option<WizardSchema> {
+        let page = self.pages.get(index)?;
+        Some(WizardSchema {
+            pages: self.pages.clone(),
+            current_page: page.id.clone(),
+            progress: (index * 100 / self.len()) as u8,
+            back_navigation: self.back_navigation,
+        })
+    }
+}
+
+/// Position of the current step in its wizard, as recorded by the executor
+#[derive(Deserialize, Default, Debug)]
+#[serde(default)]
+pub struct WizardState {
+    /// Page shown last
+    pub page: Option<String>,
+    /// Pages submitted so far, oldest first
+    pub history: Vec<String>,
+    /// Answers submitted on earlier pages, including the page just submitted
+    pub values: HashMap<String, Value>,
+    /// The plugin is called with a submission of `page`
+    pub submitted: bool,
+    /// The submission asked to go back a page
+    pub back: bool,
+}
+
+impl WizardState {
+    /// Reads the state of step `step_id`. Without one (the first page has
+    /// not been shown yet) the default state starts the wizard.
+    pub fn from_journey_data(journey_data: &HashMap<String, Value>, step_id: Option<&str>) -> Self {
+        step_id
+            .and_then(|id| journey_data.get(JOURNEY_DATA_KEY)?.get(id))
+            .and_then(|state| serde_json::from_value(state.clone()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Answer kept for a field, for pre-filling a page the user returns to
+    pub fn value(&self, name: &str) -> Option<&str> {
+        self.values.get(name).and_then(|v| v.as_str())
+    }
+}
+
+impl FormSchema {
+    /// Makes the form the page at `index` of `wizard`, shown with a stepper,
+    /// progress bar and (if allowed) a back button. Out-of-range indexes are
+    /// ignored.
+    pub fn wizard(mut self, wizard: &Wizard, index: usize) -> Self {
+        self.wizard = wizard.schema(index);
+        self
+    }
+}
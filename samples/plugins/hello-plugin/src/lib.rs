@@ -14,6 +14,13 @@ use extism_pdk::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+pub mod forms;
+
+use forms::{
+    options_data, phone_e164, Branding, Condition, FormField, FormOption, FormSchema, Rule, Wizard,
+    WizardState,
+};
+
 /// Input from the Oluso plugin executor
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -21,12 +28,84 @@ struct PluginInput {
     function: String,
     user_id: Option<String>,
     tenant_id: Option<String>,
+    /// Journey step being executed; keys per-step state such as the wizard position
+    step_id: Option<String>,
+    /// Stable across retries of the same journey step attempt; pass it to
+    /// downstream APIs so side effects happen at most once
+    idempotency_key: Option<String>,
     input: HashMap<String, serde_json::Value>,
     journey_data: HashMap<String, serde_json::Value>,
+    /// Effective configuration: platform defaults, tenant and step overrides merged
+    #[serde(default)]
+    config: HashMap<String, serde_json::Value>,
+    /// Layer (`platform`, `tenant` or `step`) that supplied each config value, by dotted path
+    #[serde(default)]
+    config_provenance: HashMap<String, String>,
+    /// Cursor returned as `nextPageToken` by the previous call, if any
+    page_token: Option<String>,
+    /// State saved by the previous invocation of a long-running job
+    checkpoint: Option<serde_json::Value>,
+    /// Messages published earlier in the journey, keyed by subscribed topic
+    #[serde(default)]
+    messages: HashMap<String, Vec<BusMessage>>,
+    /// Input of the invocation being compensated (only set for `compensate`)
+    original_input: Option<HashMap<String, serde_json::Value>>,
+    /// Output of the invocation being compensated (only set for `compensate`)
+    original_output: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// A message published on the journey's message bus by another plugin
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BusMessage {
+    payload: serde_json::Value,
+    publisher: Option<String>,
+}
+
+/// Handle to a file uploaded through a `file` form field. The executor keeps
+/// the bytes; read them with [`read_attachment`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Attachment {
+    id: String,
+    file_name: String,
+    content_type: String,
+    size: u64,
+}
+
+impl PluginInput {
+    /// Attachment handle submitted in the `field` file input, if any
+    fn attachment(&self, field: &str) -> Option<Attachment> {
+        serde_json::from_value(self.input.get(field)?.clone()).ok()
+    }
+
+    /// Messages received on `topic`, oldest first
+    fn messages(&self, topic: &str) -> &[BusMessage] {
+        self.messages.get(topic).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// Looks up a config value by dotted path, e.g. `limits.max_attempts`
+    fn config_value(&self, path: &str) -> Option<&serde_json::Value> {
+        let mut segments = path.split('.');
+        let first = self.config.get(segments.next()?)?;
+        segments.try_fold(first, |value, segment| value.get(segment))
+    }
+
+    /// Layer that supplied the config value at `path`. Values inherited as part
+    /// of a larger object report the layer of the nearest recorded ancestor.
+    fn config_source(&self, path: &str) -> Option<&str> {
+        let mut current = path;
+        loop {
+            if let Some(layer) = self.config_provenance.get(current) {
+                return Some(layer.as_str());
+            }
+            current = &current[..current.rfind('.')?];
+        }
+    }
 }
 
 /// Output to return to the Oluso plugin executor
-#[derive(Serialize)]
+#[derive(Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 struct PluginOutput {
     success: bool,
@@ -36,15 +115,21 @@ struct PluginOutput {
     action: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     data: Option<HashMap<String, serde_json::Value>>,
+    /// Cursor for the next page; omitted on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_page_token: Option<String>,
+    /// Job state to resume from; omitted once the job has finished
+    #[serde(skip_serializing_if = "Option::is_none")]
+    checkpoint: Option<serde_json::Value>,
 }
 
 impl PluginOutput {
     fn success(data: HashMap<String, serde_json::Value>) -> Self {
         Self {
             success: true,
-            error: None,
             action: Some("continue".to_string()),
             data: Some(data),
+            ..Default::default()
         }
     }
 
@@ -53,16 +138,16 @@ impl PluginOutput {
             success: false,
             error: Some(message.to_string()),
             action: Some("fail".to_string()),
-            data: None,
+            ..Default::default()
         }
     }
 
     fn require_input(data: HashMap<String, serde_json::Value>) -> Self {
         Self {
             success: true,
-            error: None,
             action: Some("require_input".to_string()),
             data: Some(data),
+            ..Default::default()
         }
     }
 
@@ -71,13 +156,121 @@ impl PluginOutput {
         output_data.insert("branchId".to_string(), serde_json::json!(branch_id));
         Self {
             success: true,
-            error: None,
             action: Some("branch".to_string()),
             data: Some(output_data),
+            ..Default::default()
+        }
+    }
+
+    /// One page of a list result. Items go under `items`; the executor keeps
+    /// calling the function with `pageToken` until `nextPageToken` is absent.
+    fn page(items: Vec<serde_json::Value>, next_page_token: Option<String>) -> Self {
+        let mut data = HashMap::new();
+        data.insert("items".to_string(), serde_json::json!(items));
+        Self {
+            success: true,
+            action: Some("continue".to_string()),
+            data: Some(data),
+            next_page_token,
+            ..Default::default()
+        }
+    }
+
+    /// An unfinished job batch. The executor persists `checkpoint` and invokes
+    /// the function again with it until a result without a checkpoint comes back.
+    fn in_progress(checkpoint: serde_json::Value, data: HashMap<String, serde_json::Value>) -> Self {
+        Self {
+            success: true,
+            action: Some("continue".to_string()),
+            data: Some(data),
+            checkpoint: Some(checkpoint),
+            ..Default::default()
         }
     }
 }
 
+/// Progress update for a long-running job
+#[derive(Serialize)]
+struct ProgressReport {
+    percent: u8,
+    message: String,
+}
+
+/// Message published to other plugins in the same journey
+#[derive(Serialize)]
+struct OutgoingMessage {
+    topic: String,
+    payload: serde_json::Value,
+}
+
+mod host {
+    use super::{OutgoingMessage, ProgressReport};
+    use extism_pdk::*;
+
+    #[host_fn]
+    extern "ExtismHost" {
+        pub fn report_progress(progress: Json<ProgressReport>);
+        pub fn publish(message: Json<OutgoingMessage>);
+        pub fn read_attachment(id: String) -> Vec<u8>;
+    }
+}
+
+/// Reports job progress to the executor. Failures are ignored so that a
+/// missing progress sink never aborts the job itself.
+fn report_progress(percent: u8, message: &str) {
+    let progress = ProgressReport {
+        percent: percent.min(100),
+        message: message.to_string(),
+    };
+    let _ = unsafe { host::report_progress(Json(progress)) };
+}
+
+/// Publishes `payload` on `topic` for plugins later in the journey that
+/// subscribe to it.
+fn publish(topic: &str, payload: serde_json::Value) -> Result<(), Error> {
+    let message = OutgoingMessage {
+        topic: topic.to_string(),
+        payload,
+    };
+    unsafe { host::publish(Json(message)) }
+}
+
+/// Reads an uploaded file's contents from the executor. Only files uploaded
+/// in the current journey can be read.
+fn read_attachment(attachment: &Attachment) -> Result<Vec<u8>, Error> {
+    unsafe { host::read_attachment(attachment.id.clone()) }
+}
+
+/// Default number of items returned per page by list functions
+const DEFAULT_PAGE_SIZE: usize = 100;
+
+/// Slices `items` into the page addressed by `page_token`.
+///
+/// Tokens are opaque to the executor; here they are simply the offset of the
+/// first item in the page. Returns the page and the token for the next one.
+fn paginate<T: Clone>(
+    items: &[T],
+    page_token: Option<&str>,
+    page_size: usize,
+) -> Result<(Vec<T>, Option<String>), String> {
+    let offset = match page_token {
+        Some(token) => token
+            .parse::<usize>()
+            .map_err(|_| format!("Invalid page token: {}", token))?,
+        None => 0,
+    };
+
+    let end = offset.saturating_add(page_size).min(items.len());
+    let page = items.get(offset..end).unwrap_or_default().to_vec();
+    let next = if end < items.len() {
+        Some(end.to_string())
+    } else {
+        None
+    };
+
+    Ok((page, next))
+}
+
 /// The main execute function called by Oluso
 /// This is the primary entry point for the plugin
 #[plugin_fn]
@@ -90,6 +283,15 @@ pub fn execute(input_json: String) -> FnResult<String> {
         "validate" => validate(&input),
         "transform" => transform(&input),
         "branch" => branch_example(&input),
+        "list_entries" => list_entries_page(&input),
+        "migrate_users" => migrate_users_batch(&input),
+        "collect_risk" => collect_risk_signal(&input),
+        "decide_mfa" => decide_mfa_requirement(&input),
+        "provision_account" => provision_downstream_account(&input),
+        "verify_code" => verify_one_time_code(&input),
+        "verify_document" => verify_uploaded_document(&input),
+        "collect_identity" => collect_identity_details(&input),
+        "onboarding" => onboarding_wizard(&input),
         _ => PluginOutput::error(&format!("Unknown function: {}", input.function)),
     };
 
@@ -109,10 +311,19 @@ fn greet(input: &PluginInput) -> PluginOutput {
 
     let user_id = input.user_id.as_deref().unwrap_or("anonymous");
 
+    let prefix = input
+        .config_value("greeting_prefix")
+        .and_then(|v| v.as_str())
+        .unwrap_or("Hello");
+
     let mut data = HashMap::new();
     data.insert(
         "greeting".to_string(),
-        serde_json::json!(format!("Hello, {}!", name)),
+        serde_json::json!(format!("{}, {}!", prefix, name)),
+    );
+    data.insert(
+        "greeting_prefix_source".to_string(),
+        serde_json::json!(input.config_source("greeting_prefix").unwrap_or("default")),
     );
     data.insert("user_id".to_string(), serde_json::json!(user_id));
     data.insert("plugin_version".to_string(), serde_json::json!("1.0.0"));
@@ -139,7 +350,7 @@ fn validate(input: &PluginInput) -> PluginOutput {
 
     if let Some(age_val) = age {
         if let Some(age_num) = age_val.as_i64() {
-            if age_num < 0 || age_num > 150 {
+            if !(0..=150).contains(&age_num) {
                 errors.push("Age must be between 0 and 150");
             }
         } else {
@@ -204,6 +415,367 @@ fn branch_example(input: &PluginInput) -> PluginOutput {
     PluginOutput::branch(branch_id, data)
 }
 
+/// List example - returns directory entries one page at a time
+fn list_entries_page(input: &PluginInput) -> PluginOutput {
+    let page_size = input
+        .input
+        .get("page_size")
+        .and_then(|v| v.as_u64())
+        .map(|n| n as usize)
+        .filter(|&n| n > 0)
+        .unwrap_or(DEFAULT_PAGE_SIZE);
+
+    // Stand-in for a directory or external API listing
+    let entries: Vec<serde_json::Value> = (1..=250)
+        .map(|i| serde_json::json!({ "id": format!("user-{}", i), "department": "engineering" }))
+        .collect();
+
+    match paginate(&entries, input.page_token.as_deref(), page_size) {
+        Ok((page, next)) => PluginOutput::page(page, next),
+        Err(e) => PluginOutput::error(&e),
+    }
+}
+
+/// Job example - migrates users in batches, checkpointing after each one
+fn migrate_users_batch(input: &PluginInput) -> PluginOutput {
+    const TOTAL_USERS: u64 = 100_000;
+    const BATCH_SIZE: u64 = 1_000;
+
+    let offset = input
+        .checkpoint
+        .as_ref()
+        .and_then(|c| c.get("offset"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0);
+
+    // Stand-in for migrating one batch of users
+    let end = (offset + BATCH_SIZE).min(TOTAL_USERS);
+
+    let percent = (end * 100 / TOTAL_USERS) as u8;
+    report_progress(percent, &format!("Migrated {} of {} users", end, TOTAL_USERS));
+
+    let mut data = HashMap::new();
+    data.insert("migrated".to_string(), serde_json::json!(end));
+
+    if end < TOTAL_USERS {
+        PluginOutput::in_progress(serde_json::json!({ "offset": end }), data)
+    } else {
+        PluginOutput::success(data)
+    }
+}
+
+/// Publisher example - scores the sign-in and shares it on `risk.score`
+fn collect_risk_signal(input: &PluginInput) -> PluginOutput {
+    let new_device = input
+        .input
+        .get("new_device")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+
+    let score = if new_device { 70 } else { 10 };
+
+    if let Err(e) = publish("risk.score", serde_json::json!({ "score": score })) {
+        return PluginOutput::error(&format!("Failed to publish risk score: {}", e));
+    }
+
+    let mut data = HashMap::new();
+    data.insert("risk_collected".to_string(), serde_json::json!(true));
+    PluginOutput::success(data)
+}
+
+/// Subscriber example - requires MFA when a published risk score is high
+fn decide_mfa_requirement(input: &PluginInput) -> PluginOutput {
+    let score = input
+        .messages("risk.score")
+        .iter()
+        .filter_map(|m| m.payload.get("score").and_then(|v| v.as_u64()))
+        .max()
+        .unwrap_or(0);
+
+    let source = input
+        .messages("risk.score")
+        .last()
+        .and_then(|m| m.publisher.as_deref())
+        .unwrap_or("none");
+
+    let mut data = HashMap::new();
+    data.insert("risk_score".to_string(), serde_json::json!(score));
+    data.insert("risk_source".to_string(), serde_json::json!(source));
+
+    if score >= 50 {
+        PluginOutput::branch("require_mfa", data)
+    } else {
+        PluginOutput::success(data)
+    }
+}
+
+/// Side-effecting example - provisions an account in a downstream system
+fn provision_downstream_account(input: &PluginInput) -> PluginOutput {
+    let user_id = input.user_id.as_deref().unwrap_or("anonymous");
+
+    // Stand-in for calling the downstream provisioning API, which should
+    // treat a repeated request id as a no-op
+    let account_id = format!("acct-{}", user_id);
+    let request_id = input.idempotency_key.as_deref().unwrap_or(user_id);
+
+    let mut data = HashMap::new();
+    data.insert("account_id".to_string(), serde_json::json!(account_id));
+    data.insert("request_id".to_string(), serde_json::json!(request_id));
+    PluginOutput::success(data)
+}
+
+/// Verify code function - asks for a one-time code, then checks it
+///
+/// The code is collected in an `otp` field, so the executor never logs it or
+/// stores it with the journey submission. Its format rule is checked by the
+/// browser before submitting and again here, from the same schema.
+fn verify_one_time_code(input: &PluginInput) -> PluginOutput {
+    let form = FormSchema::new("Verify it's you")
+        .description("Enter the 6-digit code we sent you")
+        .field(
+            FormField::otp("code", "Verification code")
+                .required()
+                .aria_description("6 digits, sent to your phone or email")
+                .rule(Rule::pattern("[0-9]{6}", "Enter the 6 digits of the code")),
+        )
+        .submit_button_text("Verify");
+
+    if !input.input.contains_key("code") {
+        return PluginOutput::require_input(form.into_data());
+    }
+
+    let errors = form.validate(&input.input);
+    if !errors.is_empty() {
+        return PluginOutput::require_input(form.with_errors(&errors).into_data());
+    }
+
+    let mut data = HashMap::new();
+    data.insert("code_verified".to_string(), serde_json::json!(true));
+    PluginOutput::success(data)
+}
+
+/// Maximum accepted document size (5 MB)
+const MAX_DOCUMENT_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Verify document function - asks for an identity document upload, then
+/// inspects the file through the attachment handle
+fn verify_uploaded_document(input: &PluginInput) -> PluginOutput {
+    let form = |error: Option<&str>| {
+        let mut schema = FormSchema::new("Upload an identity document")
+            .description("A passport or driving licence, as a PDF or photo")
+            .field(
+                FormField::file("document", "Document")
+                    .required()
+                    .accept("application/pdf,image/*")
+                    .max_size(MAX_DOCUMENT_SIZE),
+            );
+        if let Some(error) = error {
+            schema = schema.error_message(error);
+        }
+        PluginOutput::require_input(schema.into_data())
+    };
+
+    let Some(document) = input.attachment("document") else {
+        return form(None);
+    };
+
+    if document.size > MAX_DOCUMENT_SIZE {
+        return form(Some("The document must be 5 MB or smaller"));
+    }
+
+    let is_pdf = document.content_type == "application/pdf";
+    if !is_pdf && !document.content_type.starts_with("image/") {
+        return form(Some("Upload a PDF or an image"));
+    }
+
+    let bytes = match read_attachment(&document) {
+        Ok(bytes) => bytes,
+        Err(e) => return PluginOutput::error(&format!("Failed to read document: {}", e)),
+    };
+
+    // Stand-in for real document verification: check the file signature
+    if is_pdf && !bytes.starts_with(b"%PDF") {
+        return form(Some("The file is not a valid PDF"));
+    }
+
+    let mut data = HashMap::new();
+    data.insert("document_name".to_string(), serde_json::json!(document.file_name));
+    data.insert("document_type".to_string(), serde_json::json!(document.content_type));
+    data.insert("document_size".to_string(), serde_json::json!(bytes.len()));
+    PluginOutput::success(data)
+}
+
+/// Collect identity function - asks for the details an identity-proofing
+/// check needs and returns the phone number in E.164 form
+fn collect_identity_details(input: &PluginInput) -> PluginOutput {
+    let form = FormSchema::new("Confirm your details")
+        .field(FormField::markdown(
+            "We use these details to **verify your identity**.\n\n\
+             - Enter your date of birth as shown on your ID\n\
+             - Use a mobile number you can receive texts on",
+        ))
+        .field(
+            FormField::date("date_of_birth", "Date of birth")
+                .required()
+                .min_date("1900-01-01")
+                .autocomplete("bday"),
+        )
+        .field(
+            FormField::country("country", "Country of residence")
+                .required()
+                .default_country("GB"),
+        )
+        .field(
+            FormField::phone("phone", "Mobile number")
+                .required()
+                .default_country("GB")
+                .autocomplete("mobile tel-national")
+                .help_text("We'll send a code to this number"),
+        )
+        .field(FormField::link("How we use your data", "https://example.com/privacy"))
+        .branding(Branding::new().logo("identity-partner").layout("medium"));
+
+    if !input.input.contains_key("phone") {
+        return PluginOutput::require_input(form.into_data());
+    }
+
+    let errors = form.validate(&input.input);
+    if !errors.is_empty() {
+        return PluginOutput::require_input(form.with_errors(&errors).into_data());
+    }
+
+    let mut data = HashMap::new();
+    data.insert("date_of_birth".to_string(), input.input["date_of_birth"].clone());
+    data.insert("country".to_string(), input.input["country"].clone());
+    data.insert("phone_number".to_string(), serde_json::json!(phone_e164(&input.input, "phone")));
+    PluginOutput::success(data)
+}
+
+/// Multi-page onboarding shown as a stepper. The executor tracks which page
+/// was submitted and keeps the answers, so each call handles a single page.
+fn onboarding_wizard(input: &PluginInput) -> PluginOutput {
+    let wizard = Wizard::new()
+        .page("account", "Account")
+        .page("profile", "Profile")
+        .page("review", "Review");
+    let state = WizardState::from_journey_data(&input.journey_data, input.step_id.as_deref());
+
+    // A page that fails validation is shown again instead of moving on
+    if let Some(index) = wizard.submitted_page(&state) {
+        let form = onboarding_page(&wizard, index, &state);
+        let errors = form.validate(&input.input);
+        if !errors.is_empty() {
+            return PluginOutput::require_input(form.with_errors(&errors).into_data());
+        }
+    }
+
+    let position = wizard.position(&state);
+    if position < wizard.len() {
+        return PluginOutput::require_input(onboarding_page(&wizard, position, &state).into_data());
+    }
+
+    let data = ["display_name", "email", "country", "newsletter"]
+        .iter()
+        .filter_map(|name| Some((name.to_string(), state.values.get(*name)?.clone())))
+        .collect();
+    PluginOutput::success(data)
+}
+
+fn onboarding_page(wizard: &Wizard, index: usize, state: &WizardState) -> FormSchema {
+    // Pages the user returns to show their earlier answers
+    let prefill = |field: FormField, name: &str| match state.value(name) {
+        Some(value) => field.value(value),
+        None => field,
+    };
+
+    let form = match index {
+        0 => FormSchema::new("Create your account")
+            .field(prefill(
+                FormField::text("display_name", "Display name")
+                    .required()
+                    .autocomplete("nickname"),
+                "display_name",
+            ))
+            .field(prefill(
+                FormField::email("email", "Email").required().autocomplete("email"),
+                "email",
+            )),
+        1 => FormSchema::new("About you")
+            .field(prefill(
+                FormField::country("country", "Country of residence")
+                    .required()
+                    .default_country("GB"),
+                "country",
+            ))
+            .field(FormField::checkbox("newsletter", "Send me product news")),
+        _ => FormSchema::new("Review your details")
+            .field(FormField::markdown(&format!(
+                "- Display name: {}\n- Email: {}\n- Country: {}",
+                state.value("display_name").unwrap_or_default(),
+                state.value("email").unwrap_or_default(),
+                state.value("country").unwrap_or_default()
+            )))
+            .field(FormField::checkbox("accept_terms", "I accept the terms of service").required())
+            .submit_button_text("Finish"),
+    };
+
+    form.wizard(wizard, index)
+}
+
+/// Undoes the side effects of a successful call when the journey fails later.
+///
+/// `function` names the original function; `originalInput` and `originalOutput`
+/// carry what it received and returned. The executor compensates steps in
+/// reverse order, so this only needs to undo its own work.
+#[plugin_fn]
+pub fn compensate(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = undo(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Undoes the call described by a `compensate` input
+fn undo(input: &PluginInput) -> PluginOutput {
+    match input.function.as_str() {
+        "provision_account" => {
+            let account_id = input
+                .original_output
+                .as_ref()
+                .and_then(|o| o.get("account_id"))
+                .and_then(|v| v.as_str());
+
+            // The email the user signed up with, to tell them the account was not kept
+            let email = input
+                .original_input
+                .as_ref()
+                .and_then(|i| i.get("email"))
+                .and_then(|v| v.as_str());
+
+            match account_id {
+                Some(account_id) => {
+                    // Stand-in for calling the downstream deprovisioning API of the tenant
+                    let mut data = HashMap::new();
+                    data.insert("deprovisioned".to_string(), serde_json::json!(account_id));
+                    data.insert("tenant_id".to_string(), serde_json::json!(input.tenant_id));
+                    if let Some(email) = email {
+                        data.insert("notified".to_string(), serde_json::json!(email));
+                    }
+                    PluginOutput::success(data)
+                }
+                None => PluginOutput::error("Missing account_id in original output"),
+            }
+        }
+        // Nothing to undo for side-effect free functions
+        _ => PluginOutput::success(HashMap::new()),
+    }
+}
+
 /// Alternative entry point for validation
 #[plugin_fn]
 pub fn validate_input(input_json: String) -> FnResult<String> {
@@ -218,37 +790,153 @@ pub fn validate_input(input_json: String) -> FnResult<String> {
     Ok(output_json)
 }
 
+/// Entry point for listing every entry (`"entryPoint": "list_entries", "allPages": true`);
+/// the executor calls it once per page
+#[plugin_fn]
+pub fn list_entries(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = list_entries_page(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for running `migrate_users` as a job (`"entryPoint": "migrate_users", "job": true`);
+/// the executor calls it once per batch
+#[plugin_fn]
+pub fn migrate_users(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = migrate_users_batch(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the publishing step of the risk example (`"entryPoint": "collect_risk"`)
+#[plugin_fn]
+pub fn collect_risk(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = collect_risk_signal(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the subscribing step of the risk example (`"entryPoint": "decide_mfa"`)
+#[plugin_fn]
+pub fn decide_mfa(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = decide_mfa_requirement(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the side-effecting step (`"entryPoint": "provision_account"`), undone by `compensate`
+#[plugin_fn]
+pub fn provision_account(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = provision_downstream_account(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the one-time code step (`"entryPoint": "verify_code"`)
+#[plugin_fn]
+pub fn verify_code(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = verify_one_time_code(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the document upload step (`"entryPoint": "verify_document"`)
+#[plugin_fn]
+pub fn verify_document(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = verify_uploaded_document(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the identity details step (`"entryPoint": "collect_identity"`)
+#[plugin_fn]
+pub fn collect_identity(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = collect_identity_details(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+/// Entry point for the onboarding wizard (`"entryPoint": "onboarding"`)
+#[plugin_fn]
+pub fn onboarding(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let output = onboarding_wizard(&input);
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
 /// Collect additional data from user
 #[plugin_fn]
 pub fn collect_data(_input_json: String) -> FnResult<String> {
-    let mut form_schema = HashMap::new();
-    form_schema.insert("title".to_string(), serde_json::json!("Additional Information"));
-    form_schema.insert("description".to_string(), serde_json::json!("Please provide the following information"));
-    form_schema.insert("fields".to_string(), serde_json::json!([
-        {
-            "name": "company",
-            "type": "text",
-            "label": "Company Name",
-            "required": true
-        },
-        {
-            "name": "department",
-            "type": "select",
-            "label": "Department",
-            "options": [
-                {"value": "engineering", "label": "Engineering"},
-                {"value": "sales", "label": "Sales"},
-                {"value": "marketing", "label": "Marketing"},
-                {"value": "support", "label": "Support"}
-            ]
-        },
-        {
-            "name": "notes",
-            "type": "textarea",
-            "label": "Additional Notes",
-            "rows": 3
-        }
-    ]));
+    let form_schema = FormSchema::new("Additional Information")
+        .description("Please provide the following information")
+        .field(
+            FormField::select("account_type", "Account Type")
+                .required()
+                .option("personal", "Personal")
+                .option("business", "Business"),
+        )
+        .field(
+            FormField::text("company", "Company Name")
+                .required()
+                .show_if(Condition::equals("account_type", "business")),
+        )
+        .field(FormField::select("department", "Department").options_function("department_options"))
+        .field(FormField::textarea("notes", "Additional Notes", 3))
+        .into_data();
 
     let output = PluginOutput::require_input(form_schema);
 
@@ -257,3 +945,116 @@ pub fn collect_data(_input_json: String) -> FnResult<String> {
 
     Ok(output_json)
 }
+
+/// Options function for the `department` select in `collect_data`, filtered
+/// by the search text the user typed
+#[plugin_fn]
+pub fn department_options(input_json: String) -> FnResult<String> {
+    let input: PluginInput = serde_json::from_str(&input_json)
+        .map_err(|e| Error::msg(format!("Failed to parse input: {}", e)))?;
+
+    let query = input
+        .input
+        .get("__query")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    // Stand-in for a directory or external API lookup
+    let options: Vec<FormOption> = [
+        ("engineering", "Engineering"),
+        ("sales", "Sales"),
+        ("marketing", "Marketing"),
+        ("support", "Support"),
+    ]
+    .iter()
+    .map(|(value, label)| FormOption::new(value, label))
+    .filter(|option| option.label().to_lowercase().contains(&query))
+    .collect();
+
+    let output = PluginOutput::success(options_data(options));
+
+    let output_json = serde_json::to_string(&output)
+        .map_err(|e| Error::msg(format!("Failed to serialize output: {}", e)))?;
+
+    Ok(output_json)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_input(page_token: Option<&str>) -> PluginInput {
+        serde_json::from_value(serde_json::json!({
+            "function": "list_entries",
+            "input": { "page_size": 100 },
+            "journeyData": {},
+            "pageToken": page_token
+        }))
+        .unwrap()
+    }
+
+    fn compensate_input(original_output: serde_json::Value) -> PluginInput {
+        serde_json::from_value(serde_json::json!({
+            "function": "provision_account",
+            "tenantId": "tenant-1",
+            "input": {},
+            "journeyData": {},
+            "originalInput": { "email": "ada@example.com" },
+            "originalOutput": original_output
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn compensate_deprovisions_the_account_it_created() {
+        let output = undo(&compensate_input(serde_json::json!({ "account_id": "acct-user-1" })));
+
+        assert!(output.success);
+        let data = output.data.unwrap();
+        assert_eq!(data["deprovisioned"], "acct-user-1");
+        assert_eq!(data["tenant_id"], "tenant-1");
+        assert_eq!(data["notified"], "ada@example.com");
+    }
+
+    #[test]
+    fn compensate_fails_without_the_created_account() {
+        let output = undo(&compensate_input(serde_json::json!({})));
+
+        assert!(!output.success);
+    }
+
+    #[test]
+    fn list_entries_walks_every_page() {
+        let mut page_token: Option<String> = None;
+        let mut ids = Vec::new();
+        let mut pages = 0;
+
+        loop {
+            let output = list_entries_page(&list_input(page_token.as_deref()));
+            assert!(output.success);
+
+            let items = output.data.as_ref().unwrap()["items"].as_array().unwrap();
+            ids.extend(items.iter().map(|item| item["id"].as_str().unwrap().to_string()));
+            pages += 1;
+
+            match output.next_page_token {
+                Some(next) => page_token = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(ids.len(), 250);
+        assert_eq!(ids.first().map(String::as_str), Some("user-1"));
+        assert_eq!(ids.last().map(String::as_str), Some("user-250"));
+    }
+
+    #[test]
+    fn list_entries_rejects_invalid_page_token() {
+        let output = list_entries_page(&list_input(Some("next")));
+
+        assert!(!output.success);
+        assert_eq!(output.error.as_deref(), Some("Invalid page token: next"));
+    }
+}
@@ -120,6 +120,30 @@ public class TenantBrandingSettings
     public static TenantBrandingSettings Default => new();
 }
 
+/// <summary>
+/// Branding that plugins may apply to their own journey screens (require_input forms).
+/// Read as the "PluginBranding" section of the tenant configuration, or Oluso:PluginBranding.
+/// </summary>
+public class PluginBrandingSettings
+{
+    /// <summary>
+    /// Logos plugins can show, by the reference they use (e.g. "partner-a" → logo URL)
+    /// </summary>
+    public Dictionary<string, string> Logos { get; set; } = new();
+
+    /// <summary>
+    /// Accent colors plugins may use (hex, e.g. "#1a73e8"). Empty: plugins keep the tenant's colors.
+    /// </summary>
+    public List<string> AccentColors { get; set; } = new();
+
+    /// <summary>
+    /// Journey layouts plugins may use (narrow, medium, wide, full). Empty: all layouts are allowed.
+    /// </summary>
+    public List<string> Layouts { get; set; } = new();
+
+    public static PluginBrandingSettings Default => new();
+}
+
 /// <summary>
 /// Protocol settings for a tenant (OIDC discovery document configuration).
 /// Controls what capabilities are advertised and enabled for this tenant.
@@ -264,6 +264,10 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
             state = state with { Status = JourneyStatus.Expired };
             await _stateStore.SaveAsync(state, cancellationToken);
 
+            // An abandoned journey failed as far as its plugin steps are concerned
+            await CompensatePluginStepsAsync(journeyId, cancellationToken);
+            await ClearPluginJourneyDataAsync(journeyId, cancellationToken);
+
             return new JourneyResult
             {
                 JourneyId = journeyId,
@@ -287,25 +291,13 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
         var policy = await _policyStore.GetByIdAsync(state.PolicyId, cancellationToken);
         if (policy == null)
         {
-            return new JourneyResult
-            {
-                JourneyId = journeyId,
-                Status = JourneyStatus.Failed,
-                Error = "policy_not_found",
-                ErrorDescription = "Journey policy not found"
-            };
+            return await FailJourneyAsync(journeyId, "policy_not_found", "Journey policy not found", cancellationToken);
         }
 
         var currentStep = policy.Steps.FirstOrDefault(s => s.Id == state.CurrentStepId);
         if (currentStep == null)
         {
-            return new JourneyResult
-            {
-                JourneyId = journeyId,
-                Status = JourneyStatus.Failed,
-                Error = "step_not_found",
-                ErrorDescription = "Current step not found in policy"
-            };
+            return await FailJourneyAsync(journeyId, "step_not_found", "Current step not found in policy", cancellationToken);
         }
 
         return await ExecuteStepAsync(journeyId, currentStep, policy, input, cancellationToken);
@@ -367,13 +359,8 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
             if (missingClaims.Count > 0)
             {
                 _logger.LogWarning("Step {StepId} missing required claims: {Claims}", step.Id, string.Join(", ", missingClaims));
-                return new JourneyResult
-                {
-                    JourneyId = journeyId,
-                    Status = JourneyStatus.Failed,
-                    Error = "missing_claims",
-                    ErrorDescription = $"Required claims not present: {string.Join(", ", missingClaims)}"
-                };
+                return await FailJourneyAsync(journeyId, "missing_claims",
+                    $"Required claims not present: {string.Join(", ", missingClaims)}", cancellationToken);
             }
         }
 
@@ -382,13 +369,7 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
         if (handler == null)
         {
             _logger.LogWarning("No handler found for step type {StepType}", step.Type);
-            return new JourneyResult
-            {
-                JourneyId = journeyId,
-                Status = JourneyStatus.Failed,
-                Error = "handler_not_found",
-                ErrorDescription = $"No handler for step type: {step.Type}"
-            };
+            return await FailJourneyAsync(journeyId, "handler_not_found", $"No handler for step type: {step.Type}", cancellationToken);
         }
 
         // Resolve timeout: step-level overrides policy-level default
@@ -528,13 +509,7 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
                 StepOutcome.Failed => await HandleStepFailure(journeyId, step, policy,
                     result.Error ?? "step_failed", result.ErrorDescription, cancellationToken),
 
-                _ => new JourneyResult
-                {
-                    JourneyId = journeyId,
-                    Status = JourneyStatus.Failed,
-                    Error = "unknown_outcome",
-                    ErrorDescription = "Step returned unknown outcome"
-                }
+                _ => await FailJourneyAsync(journeyId, "unknown_outcome", "Step returned unknown outcome", cancellationToken)
             };
         }
         catch (Exception ex)
@@ -609,13 +584,7 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
         var step = policy.Steps.FirstOrDefault(s => s.Id == stepId);
         if (step == null)
         {
-            return new JourneyResult
-            {
-                JourneyId = journeyId,
-                Status = JourneyStatus.Failed,
-                Error = "branch_step_not_found",
-                ErrorDescription = $"Branch target step not found: {stepId}"
-            };
+            return await FailJourneyAsync(journeyId, "branch_step_not_found", $"Branch target step not found: {stepId}", cancellationToken);
         }
 
         state = state with { CurrentStepId = stepId };
@@ -632,7 +601,8 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
         string? errorDescription,
         CancellationToken cancellationToken)
     {
-        // If OnFailure is defined, navigate to that step instead of failing
+        // If OnFailure is defined, navigate to that step instead of failing. The journey may still
+        // recover, so plugin steps are only compensated once it fails, is cancelled or expires.
         if (!string.IsNullOrEmpty(step.OnFailure))
         {
             _logger.LogDebug("Step {StepId} failed, navigating to OnFailure step {OnFailureStep}",
@@ -651,7 +621,27 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
             return await MoveToStepAsync(journeyId, policy, step.OnFailure, cancellationToken);
         }
 
-        // No OnFailure defined, fail the journey
+        return await FailJourneyAsync(journeyId, error, errorDescription, cancellationToken);
+    }
+
+    /// <summary>
+    /// Ends the journey as failed, undoing side-effecting plugin steps first
+    /// </summary>
+    private async Task<JourneyResult> FailJourneyAsync(
+        string journeyId,
+        string error,
+        string? errorDescription,
+        CancellationToken cancellationToken)
+    {
+        await CompensatePluginStepsAsync(journeyId, cancellationToken);
+        await ClearPluginJourneyDataAsync(journeyId, cancellationToken);
+
+        var state = await _stateStore.GetAsync(journeyId, cancellationToken);
+        if (state != null)
+        {
+            await _stateStore.SaveAsync(state with { Status = JourneyStatus.Failed }, cancellationToken);
+        }
+
         return new JourneyResult
         {
             JourneyId = journeyId,
@@ -661,6 +651,97 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
         };
     }
 
+    /// <summary>
+    /// Runs plugin compensations for a failed, cancelled or expired journey, most recent step first (saga order).
+    /// Failures are logged and do not stop the remaining compensations.
+    /// </summary>
+    private async Task CompensatePluginStepsAsync(string journeyId, CancellationToken cancellationToken)
+    {
+        var state = await _stateStore.GetAsync(journeyId, cancellationToken);
+        var entries = PluginCompensationEntry.FromJourneyData(state?.Data);
+        if (state?.Data == null || entries.Count == 0)
+        {
+            return;
+        }
+
+        var pluginExecutor = _serviceProvider.GetService<IPluginExecutor>();
+        if (pluginExecutor == null)
+        {
+            _logger.LogWarning("Journey {JourneyId} has {Count} plugin steps to compensate but no plugin executor is registered",
+                journeyId, entries.Count);
+            return;
+        }
+
+        for (var i = entries.Count - 1; i >= 0; i--)
+        {
+            var entry = entries[i];
+            try
+            {
+                var context = new PluginExecutionContext
+                {
+                    UserId = state.UserId,
+                    TenantId = state.TenantId,
+                    JourneyId = journeyId,
+                    Input = entry.Input,
+                    JourneyData = state.Data
+                };
+
+                var result = await pluginExecutor.CompensateAsync(
+                    entry.PluginName, entry.FunctionName, context, entry.Output, cancellationToken);
+
+                if (result.Success)
+                {
+                    _logger.LogInformation("Compensated step {StepId} ({PluginName}.{FunctionName}) in journey {JourneyId}",
+                        entry.StepId, entry.PluginName, entry.FunctionName, journeyId);
+                }
+                else
+                {
+                    _logger.LogWarning("Compensation of step {StepId} in journey {JourneyId} failed: {Error}",
+                        entry.StepId, journeyId, result.Error);
+                }
+            }
+            catch (Exception ex)
+            {
+                _logger.LogError(ex, "Error compensating step {StepId} in journey {JourneyId}", entry.StepId, journeyId);
+            }
+        }
+
+        state.Data.Remove(PluginCompensationEntry.JourneyDataKey);
+        await _stateStore.SaveAsync(state, cancellationToken);
+    }
+
+    /// <summary>
+    /// Removes the files uploaded to plugin forms and the messages plugins published once the journey has ended
+    /// </summary>
+    private async Task ClearPluginJourneyDataAsync(string journeyId, CancellationToken cancellationToken)
+    {
+        try
+        {
+            var attachmentStore = _serviceProvider.GetService<IPluginAttachmentStore>();
+            if (attachmentStore != null)
+            {
+                await attachmentStore.ClearAsync(journeyId, cancellationToken);
+            }
+        }
+        catch (Exception ex)
+        {
+            _logger.LogWarning(ex, "Failed to clear attachments of journey {JourneyId}", journeyId);
+        }
+
+        try
+        {
+            var messageBus = _serviceProvider.GetService<IPluginMessageBus>();
+            if (messageBus != null)
+            {
+                await messageBus.ClearAsync(journeyId, cancellationToken);
+            }
+        }
+        catch (Exception ex)
+        {
+            _logger.LogWarning(ex, "Failed to clear plugin messages of journey {JourneyId}", journeyId);
+        }
+    }
+
     private async Task<JourneyResult> CompleteJourneyAsync(
         string journeyId,
         JourneyState state,
@@ -675,6 +756,8 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
 
         _logger.LogInformation("Journey {JourneyId} completed successfully", journeyId);
 
+        await ClearPluginJourneyDataAsync(journeyId, cancellationToken);
+
         // Handle data collection submission persistence
         if (policy.PersistSubmissions && _submissionStore != null)
         {
@@ -754,16 +837,21 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
                 "idp_restrictions", "domain_hint", "requires_authentication", "persist_submissions",
                 "success_message", "success_redirect_url", "redirectUri", "state", "nonce",
                 "completedSteps", "ip_address", "user_agent", "referrer", "country", "locale",
-                "lastError", "lastErrorDescription", "failedStepId"
+                "lastError", "lastErrorDescription", "failedStepId",
+                PluginCompensationEntry.JourneyDataKey, PluginExecutionContext.StepAttemptsDataKey, DynamicFormSchema.SensitiveFieldsDataKey,
+                PluginFieldOptionsSource.JourneyDataKey, PluginWizardState.JourneyDataKey, PluginFileField.JourneyDataKey
             };
 
+            // Values collected through masked fields are recorded as redacted
+            var sensitiveFields = DynamicFormSchema.GetSensitiveFields(state.Data);
+
             if (state.Data != null)
             {
                 foreach (var kvp in state.Data)
                 {
                     if (!systemFields.Contains(kvp.Key))
                     {
-                        submittedData[kvp.Key] = kvp.Value;
+                        submittedData[kvp.Key] = sensitiveFields.Contains(kvp.Key) ? "[REDACTED]" : kvp.Value;
                     }
                 }
             }
@@ -862,6 +950,9 @@ public class DefaultJourneyOrchestrator : IJourneyOrchestrator
             state = state with { Status = JourneyStatus.Cancelled };
             await _stateStore.SaveAsync(state, cancellationToken);
             _logger.LogInformation("Journey {JourneyId} cancelled", journeyId);
+
+            await CompensatePluginStepsAsync(journeyId, cancellationToken);
+            await ClearPluginJourneyDataAsync(journeyId, cancellationToken);
         }
     }
 }
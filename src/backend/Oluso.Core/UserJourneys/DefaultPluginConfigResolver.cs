@@ -0,0 +1,106 @@
+using System.Text.Json;
+using System.Text.Json.Nodes;
+using Microsoft.Extensions.Logging;
+using Oluso.Core.Domain.Interfaces;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Default plugin configuration resolver. Platform and tenant layers come from the
+/// plugin store's registrations; without a plugin store only step overrides apply.
+/// </summary>
+public class DefaultPluginConfigResolver : IPluginConfigResolver
+{
+    private readonly IPluginStore? _pluginStore;
+    private readonly ILogger<DefaultPluginConfigResolver> _logger;
+
+    public DefaultPluginConfigResolver(
+        ILogger<DefaultPluginConfigResolver> logger,
+        IPluginStore? pluginStore = null)
+    {
+        _logger = logger;
+        _pluginStore = pluginStore;
+    }
+
+    public async Task<ResolvedPluginConfig> ResolveAsync(
+        string pluginName,
+        string? tenantId,
+        IDictionary<string, object>? stepOverrides,
+        CancellationToken cancellationToken = default)
+    {
+        var merged = new JsonObject();
+        var provenance = new Dictionary<string, string>();
+
+        if (_pluginStore != null)
+        {
+            var registrations = (await _pluginStore.GetAvailablePluginsAsync(tenantId, cancellationToken))
+                .Where(p => p.Name == pluginName)
+                .ToList();
+
+            var platform = registrations.FirstOrDefault(p => p.TenantId == null);
+            var tenant = tenantId != null ? registrations.FirstOrDefault(p => p.TenantId == tenantId) : null;
+
+            Merge(merged, ParseLayer(pluginName, PluginConfigLayers.Platform, platform?.DefaultConfig),
+                PluginConfigLayers.Platform, "", provenance);
+            Merge(merged, ParseLayer(pluginName, PluginConfigLayers.Tenant, tenant?.DefaultConfig),
+                PluginConfigLayers.Tenant, "", provenance);
+        }
+
+        if (stepOverrides != null)
+        {
+            Merge(merged, JsonSerializer.SerializeToNode(stepOverrides) as JsonObject,
+                PluginConfigLayers.Step, "", provenance);
+        }
+
+        return new ResolvedPluginConfig
+        {
+            Values = merged.Deserialize<Dictionary<string, object>>() ?? new Dictionary<string, object>(),
+            Provenance = provenance
+        };
+    }
+
+    private JsonObject? ParseLayer(string pluginName, string layer, string? json)
+    {
+        if (string.IsNullOrWhiteSpace(json)) return null;
+
+        try
+        {
+            return JsonNode.Parse(json) as JsonObject;
+        }
+        catch (JsonException ex)
+        {
+            _logger.LogWarning(ex, "Ignoring invalid {Layer} configuration for plugin {PluginName}", layer, pluginName);
+            return null;
+        }
+    }
+
+    private static void Merge(
+        JsonObject target,
+        JsonObject? source,
+        string layer,
+        string prefix,
+        IDictionary<string, string> provenance)
+    {
+        if (source == null) return;
+
+        foreach (var (key, value) in source)
+        {
+            var path = prefix.Length == 0 ? key : $"{prefix}.{key}";
+
+            if (value is JsonObject sourceObject && target[key] is JsonObject targetObject)
+            {
+                Merge(targetObject, sourceObject, layer, path, provenance);
+                continue;
+            }
+
+            // A replaced value hides whatever lower layers contributed beneath it
+            foreach (var child in provenance.Keys.Where(k => k.StartsWith(path + ".", StringComparison.Ordinal)).ToList())
+            {
+                provenance.Remove(child);
+            }
+
+            target[key] = value?.DeepClone();
+            provenance[path] = layer;
+        }
+    }
+}
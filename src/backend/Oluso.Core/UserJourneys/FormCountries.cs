@@ -0,0 +1,67 @@
+using System.Globalization;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Countries offered by country and phone form fields, keyed by ISO 3166-1 alpha-2 code
+/// </summary>
+public static class FormCountries
+{
+    private static readonly Lazy<IReadOnlyDictionary<string, string>> Names = new(LoadNames);
+
+    /// <summary>
+    /// Country options (code, English name) ordered by name: all countries known to the runtime,
+    /// or only <paramref name="codes"/> when given
+    /// </summary>
+    public static IReadOnlyList<DynamicFormOption> Options(IEnumerable<string>? codes)
+    {
+        var selected = codes != null
+            ? codes.Select(c => c.ToUpperInvariant()).Distinct()
+            : Names.Value.Keys;
+
+        return selected
+            .Select(code => new DynamicFormOption { Value = code, Label = Names.Value.GetValueOrDefault(code, code) })
+            .OrderBy(o => o.Label, StringComparer.CurrentCulture)
+            .ToList();
+    }
+
+    /// <summary>
+    /// Whether <paramref name="code"/> is a known ISO 3166-1 alpha-2 country code.
+    /// Without culture data (invariant globalization) any two-letter code is accepted.
+    /// </summary>
+    public static bool IsKnown(string? code)
+    {
+        if (code is not { Length: 2 } || !code.All(char.IsAsciiLetter))
+        {
+            return false;
+        }
+
+        return Names.Value.Count == 0 || Names.Value.ContainsKey(code.ToUpperInvariant());
+    }
+
+    private static IReadOnlyDictionary<string, string> LoadNames()
+    {
+        var names = new Dictionary<string, string>(StringComparer.OrdinalIgnoreCase);
+
+        foreach (var culture in CultureInfo.GetCultures(CultureTypes.SpecificCultures))
+        {
+            RegionInfo region;
+            try
+            {
+                region = new RegionInfo(culture.Name);
+            }
+            catch (ArgumentException)
+            {
+                continue;
+            }
+
+            var code = region.TwoLetterISORegionName;
+            if (code.Length == 2 && code.All(char.IsAsciiLetter))
+            {
+                names.TryAdd(code.ToUpperInvariant(), region.EnglishName);
+            }
+        }
+
+        return names;
+    }
+}
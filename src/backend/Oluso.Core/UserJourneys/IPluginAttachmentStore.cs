@@ -0,0 +1,67 @@
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Host-side storage for files uploaded through plugin forms. Plugins receive a
+/// <see cref="PluginAttachment"/> handle in their input and read the bytes through
+/// the read_attachment host function, so uploads never travel through plugin JSON.
+/// </summary>
+public interface IPluginAttachmentStore
+{
+    /// <summary>
+    /// Largest upload accepted, in bytes
+    /// </summary>
+    long MaxSizeBytes { get; }
+
+    /// <summary>
+    /// Stores an uploaded file for a journey and returns its handle
+    /// </summary>
+    Task<PluginAttachment> StoreAsync(
+        string journeyId,
+        string fileName,
+        string contentType,
+        Stream content,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Gets an attachment's handle
+    /// </summary>
+    Task<PluginAttachment?> GetAsync(string attachmentId, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Reads an attachment's bytes
+    /// </summary>
+    Task<byte[]?> ReadAsync(string attachmentId, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Gets the handles of every attachment uploaded in a journey
+    /// </summary>
+    Task<IReadOnlyList<PluginAttachment>> ListAsync(string journeyId, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Removes all attachments for a journey
+    /// </summary>
+    Task ClearAsync(string journeyId, CancellationToken cancellationToken = default);
+}
+
+/// <summary>
+/// Handle to an uploaded file, passed to plugins in place of the file contents
+/// </summary>
+public class PluginAttachment
+{
+    public required string Id { get; init; }
+
+    /// <summary>
+    /// Journey the file was uploaded in; only plugins running in that journey can read it
+    /// </summary>
+    public required string JourneyId { get; init; }
+
+    public required string FileName { get; init; }
+    public required string ContentType { get; init; }
+
+    /// <summary>
+    /// Size in bytes
+    /// </summary>
+    public long Size { get; init; }
+
+    public DateTime UploadedAt { get; init; } = DateTime.UtcNow;
+}
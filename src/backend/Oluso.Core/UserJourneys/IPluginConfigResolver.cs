@@ -0,0 +1,49 @@
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Resolves the effective configuration for a plugin invocation by merging layers
+/// in increasing order of precedence:
+/// <list type="number">
+/// <item><description>Platform defaults - DefaultConfig of the global plugin registration</description></item>
+/// <item><description>Tenant overrides - DefaultConfig of the tenant's own plugin registration</description></item>
+/// <item><description>Journey step overrides - the step's <c>config</c> setting</description></item>
+/// </list>
+/// Objects are merged key by key; any other value (including arrays) replaces the lower layer.
+/// </summary>
+public interface IPluginConfigResolver
+{
+    /// <summary>
+    /// Resolves the effective configuration for a plugin
+    /// </summary>
+    Task<ResolvedPluginConfig> ResolveAsync(
+        string pluginName,
+        string? tenantId,
+        IDictionary<string, object>? stepOverrides,
+        CancellationToken cancellationToken = default);
+}
+
+/// <summary>
+/// Effective plugin configuration plus where each value came from
+/// </summary>
+public class ResolvedPluginConfig
+{
+    /// <summary>
+    /// Merged configuration values
+    /// </summary>
+    public IDictionary<string, object> Values { get; init; } = new Dictionary<string, object>();
+
+    /// <summary>
+    /// Layer that supplied each value, keyed by dotted path (e.g., "limits.maxAttempts" => "tenant")
+    /// </summary>
+    public IDictionary<string, string> Provenance { get; init; } = new Dictionary<string, string>();
+}
+
+/// <summary>
+/// Names of the configuration layers reported in provenance metadata
+/// </summary>
+public static class PluginConfigLayers
+{
+    public const string Platform = "platform";
+    public const string Tenant = "tenant";
+    public const string Step = "step";
+}
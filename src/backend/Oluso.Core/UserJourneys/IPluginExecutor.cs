@@ -1,3 +1,8 @@
+using System.Globalization;
+using System.Text.Json;
+using System.Text.Json.Serialization;
+using Oluso.Core.Domain.Interfaces;
+
 namespace Oluso.Core.UserJourneys;
 
 /// <summary>
@@ -14,6 +19,54 @@ public interface IPluginExecutor
         PluginExecutionContext context,
         CancellationToken cancellationToken = default);
 
+    /// <summary>
+    /// Executes a list-returning plugin function, following <c>nextPageToken</c>
+    /// until the last page and concatenating the <c>items</c> of every page
+    /// </summary>
+    Task<PluginExecutionResult> ExecuteAllPagesAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Runs a plugin function as a long-running job, re-invoking it with the saved
+    /// checkpoint until it returns without one. Resumes from the stored checkpoint
+    /// when a job with the same ID is already running.
+    /// </summary>
+    Task<PluginJobState> RunJobAsync(
+        string pluginName,
+        string functionName,
+        string jobId,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Checks if a plugin can undo its side effects (WASM plugins export <c>compensate</c>)
+    /// </summary>
+    bool SupportsCompensation(string pluginName);
+
+    /// <summary>
+    /// Undoes the side effects of an earlier successful invocation of <paramref name="functionName"/>.
+    /// The context input is the original input; plugins without compensation succeed as a no-op.
+    /// </summary>
+    Task<PluginExecutionResult> CompensateAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        IDictionary<string, object>? originalOutput,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Calls a select field's <c>optionsFunction</c> with the partial form state in the context input
+    /// and returns the <c>options</c> it produced (empty if the call fails)
+    /// </summary>
+    Task<IReadOnlyList<DynamicFormOption>> GetFieldOptionsAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken = default);
+
     /// <summary>
     /// Gets information about a loaded plugin
     /// </summary>
@@ -52,8 +105,96 @@ public class PluginExecutionContext
 {
     public string? UserId { get; init; }
     public string? TenantId { get; init; }
+
+    /// <summary>
+    /// Journey instance the plugin runs in; scopes the plugin message bus
+    /// </summary>
+    public string? JourneyId { get; init; }
+
+    /// <summary>
+    /// Journey step the plugin runs in
+    /// </summary>
+    public string? StepId { get; init; }
+
+    /// <summary>
+    /// Stable key for this logical invocation (journey instance + step + attempt).
+    /// Retries share the key, so the executor returns the cached result instead of re-running side effects.
+    /// </summary>
+    public string? IdempotencyKey { get; init; }
+
     public IDictionary<string, object>? Input { get; init; }
     public IDictionary<string, object>? JourneyData { get; init; }
+
+    /// <summary>
+    /// Effective plugin configuration (platform, tenant and step layers merged)
+    /// </summary>
+    public IDictionary<string, object>? Config { get; init; }
+
+    /// <summary>
+    /// Layer that supplied each configuration value, keyed by dotted path
+    /// </summary>
+    public IDictionary<string, string>? ConfigProvenance { get; init; }
+
+    /// <summary>
+    /// Cursor for paginated functions, taken from the previous page's <see cref="PluginExecutionResult.NextPageToken"/>
+    /// </summary>
+    public string? PageToken { get; init; }
+
+    /// <summary>
+    /// Checkpoint (JSON) saved by the previous invocation of a long-running job
+    /// </summary>
+    public string? Checkpoint { get; init; }
+
+    /// <summary>
+    /// Message bus topics whose messages are delivered to the plugin
+    /// </summary>
+    public IReadOnlyCollection<string>? Subscriptions { get; init; }
+
+    /// <summary>
+    /// Messages published in the journey on the subscribed topics, oldest first
+    /// </summary>
+    public IReadOnlyDictionary<string, IReadOnlyList<PluginMessage>>? Messages { get; init; }
+
+    /// <summary>
+    /// Masked fields (password, otp, masked_text) declared by the forms of this step.
+    /// Their values reach the plugin even when the name is on the sanitizer's deny list.
+    /// </summary>
+    public IReadOnlyCollection<string>? SensitiveInputFields { get; init; }
+
+    /// <summary>
+    /// Journey data key counting the completed attempts of each plugin step, keyed by step id
+    /// </summary>
+    public const string StepAttemptsDataKey = "pluginStepAttempts";
+
+    /// <summary>
+    /// Builds the idempotency key for an attempt of a journey step
+    /// </summary>
+    public static string CreateIdempotencyKey(string journeyId, string stepId, int attempt)
+        => $"{journeyId}:{stepId}:{attempt}";
+
+    /// <summary>
+    /// Copy of this context for one of the invocations that make up a call, such as a page of a list
+    /// or a batch of a job. Everything but the cursor is carried over, including the sensitive input
+    /// fields; the idempotency key is scoped to <paramref name="invocation"/> so one invocation is
+    /// never answered with another's cached result.
+    /// </summary>
+    public PluginExecutionContext ForInvocation(string invocation, string? pageToken = null, string? checkpoint = null) => new()
+    {
+        UserId = UserId,
+        TenantId = TenantId,
+        JourneyId = JourneyId,
+        StepId = StepId,
+        IdempotencyKey = IdempotencyKey != null ? $"{IdempotencyKey}:{invocation}" : null,
+        Input = Input,
+        JourneyData = JourneyData,
+        Config = Config,
+        ConfigProvenance = ConfigProvenance,
+        PageToken = pageToken,
+        Checkpoint = checkpoint,
+        Subscriptions = Subscriptions,
+        Messages = Messages,
+        SensitiveInputFields = SensitiveInputFields
+    };
 }
 
 /// <summary>
@@ -65,6 +206,79 @@ public class PluginExecutionResult
     public string? Error { get; init; }
     public IDictionary<string, object>? Output { get; init; }
     public PluginAction Action { get; init; } = PluginAction.Continue;
+
+    /// <summary>
+    /// Cursor for the next page of a list result; null on the last page
+    /// </summary>
+    public string? NextPageToken { get; init; }
+
+    /// <summary>
+    /// Checkpoint (JSON) to resume a long-running job from; null once the job has finished
+    /// </summary>
+    public string? Checkpoint { get; init; }
+
+    /// <summary>
+    /// Messages a managed plugin publishes on the journey's message bus once the call returns
+    /// (WASM plugins use the publish host function)
+    /// </summary>
+    public IReadOnlyList<PluginMessage>? PublishedMessages { get; init; }
+
+    /// <summary>
+    /// Whether the invocation finished its work, as opposed to failing or asking for input
+    /// </summary>
+    public bool IsCompleted => Success && Action is PluginAction.Continue or PluginAction.Complete or PluginAction.Branch;
+}
+
+/// <summary>
+/// Record of a successful side-effecting plugin step, kept in journey data so the
+/// step can be compensated if the journey later fails
+/// </summary>
+public class PluginCompensationEntry
+{
+    /// <summary>
+    /// Journey data key holding the compensation log, oldest entry first
+    /// </summary>
+    public const string JourneyDataKey = "pluginCompensations";
+
+    private static readonly JsonSerializerOptions JsonOptions = new()
+    {
+        PropertyNameCaseInsensitive = true
+    };
+
+    public required string StepId { get; init; }
+    public required string PluginName { get; init; }
+    public required string FunctionName { get; init; }
+
+    /// <summary>
+    /// Original (sanitized) input of the step
+    /// </summary>
+    public IDictionary<string, object>? Input { get; init; }
+
+    /// <summary>
+    /// Original output of the step
+    /// </summary>
+    public IDictionary<string, object>? Output { get; init; }
+
+    public DateTime CompletedAt { get; init; } = DateTime.UtcNow;
+
+    /// <summary>
+    /// Reads the compensation log from journey data, whether still in memory or round-tripped through JSON
+    /// </summary>
+    public static List<PluginCompensationEntry> FromJourneyData(IDictionary<string, object>? journeyData)
+    {
+        if (journeyData == null || !journeyData.TryGetValue(JourneyDataKey, out var value))
+        {
+            return new List<PluginCompensationEntry>();
+        }
+
+        return value switch
+        {
+            IEnumerable<PluginCompensationEntry> entries => entries.ToList(),
+            JsonElement { ValueKind: JsonValueKind.Array } element =>
+                element.Deserialize<List<PluginCompensationEntry>>(JsonOptions) ?? new List<PluginCompensationEntry>(),
+            _ => new List<PluginCompensationEntry>()
+        };
+    }
 }
 
 /// <summary>
@@ -116,6 +330,38 @@ public class PluginExecutorOptions
     /// Maximum memory (bytes) a plugin can use
     /// </summary>
     public long MaxMemoryBytes { get; set; } = 100 * 1024 * 1024; // 100MB
+
+    /// <summary>
+    /// Maximum number of pages fetched by ExecuteAllPagesAsync before giving up
+    /// </summary>
+    public int MaxPages { get; set; } = 1000;
+
+    /// <summary>
+    /// Maximum number of invocations RunJobAsync makes before failing the job
+    /// </summary>
+    public int MaxJobInvocations { get; set; } = 10000;
+
+    /// <summary>
+    /// How often the job processor looks for running jobs to start or resume
+    /// </summary>
+    public TimeSpan JobPollInterval { get; set; } = TimeSpan.FromSeconds(10);
+
+    /// <summary>
+    /// Maximum number of jobs the job processor runs at the same time
+    /// </summary>
+    public int MaxConcurrentJobs { get; set; } = 4;
+
+    /// <summary>
+    /// How long a job processor's claim on a job lasts without being renewed. The processor renews it
+    /// while the job runs; if the processor stops, another one sharing the job store takes the job over
+    /// once the lease has expired.
+    /// </summary>
+    public TimeSpan JobLeaseDuration { get; set; } = TimeSpan.FromMinutes(2);
+
+    /// <summary>
+    /// How long completed results are kept for deduplicating invocations with the same idempotency key
+    /// </summary>
+    public TimeSpan IdempotencyWindow { get; set; } = TimeSpan.FromHours(24);
 }
 
 /// <summary>
@@ -172,6 +418,22 @@ public interface IManagedPlugin
     /// Plugins that provide custom claims for tokens should implement this.
     /// </summary>
     IPluginClaimsProvider? GetClaimsProvider() => null;
+
+    /// <summary>
+    /// Whether this plugin can undo its side effects via <see cref="CompensateAsync"/>
+    /// </summary>
+    bool SupportsCompensation => false;
+
+    /// <summary>
+    /// Undoes the side effects of an earlier successful invocation when the journey fails.
+    /// The context input is the original input.
+    /// </summary>
+    Task<PluginExecutionResult> CompensateAsync(
+        string functionName,
+        PluginExecutionContext context,
+        IDictionary<string, object>? originalOutput,
+        CancellationToken cancellationToken = default)
+        => Task.FromResult(new PluginExecutionResult { Success = true });
 }
 
 /// <summary>
@@ -323,6 +585,170 @@ public class DynamicFormSchema
     /// Custom CSS class for the form container
     /// </summary>
     public string? CssClass { get; set; }
+
+    /// <summary>
+    /// Theming hints for the form screen, applied only as far as the tenant's plugin branding allows
+    /// </summary>
+    public DynamicFormBranding? Branding { get; set; }
+
+    /// <summary>
+    /// Multi-page structure when the form is one page of a wizard (rendered as a stepper)
+    /// </summary>
+    public DynamicFormWizard? Wizard { get; set; }
+
+    /// <summary>
+    /// Journey data key listing the names of masked fields collected during the journey, keyed by
+    /// the id of the step whose form declared them. Their values are redacted from journey submissions.
+    /// </summary>
+    public const string SensitiveFieldsDataKey = "sensitiveFields";
+
+    /// <summary>
+    /// Checks the schema for authoring mistakes before it is rendered.
+    /// Returns one message per problem; an empty list means the schema is valid.
+    /// </summary>
+    /// <remarks>
+    /// Covers show_if conditions, validation rules, content blocks, branding, the wizard,
+    /// options functions and per-field hints (input mode, autocomplete, dates, countries, max size).
+    /// </remarks>
+    public IReadOnlyList<string> Validate()
+    {
+        var errors = new List<string>();
+        var names = Fields.Where(f => !f.IsContent).Select(f => f.Name).ToHashSet(StringComparer.OrdinalIgnoreCase);
+
+        foreach (var block in Fields.Where(f => f.IsContent))
+        {
+            var error = block.ValidateContent();
+            if (error != null)
+                errors.Add($"Content block {Fields.IndexOf(block) + 1} ({block.Type}) {error}");
+        }
+
+        if (Branding != null)
+        {
+            errors.AddRange(Branding.Validate());
+        }
+
+        if (Wizard != null)
+        {
+            errors.AddRange(Wizard.Validate());
+        }
+
+        foreach (var field in Fields.Where(f => f.ShowIf != null))
+        {
+            var condition = field.ShowIf!;
+
+            if (string.Equals(condition.Field, field.Name, StringComparison.OrdinalIgnoreCase))
+                errors.Add($"Field '{field.Name}' cannot depend on itself in show_if");
+            else if (!names.Contains(condition.Field))
+                errors.Add($"Field '{field.Name}' has show_if on unknown field '{condition.Field}'");
+
+            if (!DynamicFormCondition.Operators.Contains(condition.Operator))
+                errors.Add($"Field '{field.Name}' has unsupported show_if operator '{condition.Operator}'");
+        }
+
+        foreach (var field in Fields)
+        {
+            foreach (var rule in field.Validation ?? Enumerable.Empty<DynamicFormValidationRule>())
+            {
+                var error = rule.Validate(names);
+                if (error != null)
+                    errors.Add($"Field '{field.Name}' has an invalid {rule.Type} rule: {error}");
+            }
+        }
+
+        foreach (var field in Fields.Where(f => f.OptionsFunction != null && f.Type != "select"))
+        {
+            errors.Add($"Field '{field.Name}' has an options function but is not a select");
+        }
+
+        foreach (var field in Fields)
+        {
+            if (field.InputMode != null && !DynamicFormField.InputModes.Contains(field.InputMode))
+                errors.Add($"Field '{field.Name}' has unsupported input mode '{field.InputMode}'");
+            if (field.Autocomplete != null && !IsAutocompleteHint(field.Autocomplete))
+                errors.Add($"Field '{field.Name}' has malformed autocomplete hint '{field.Autocomplete}'");
+
+            if (field.MinDate != null && !IsDate(field.MinDate))
+                errors.Add($"Field '{field.Name}' has min date '{field.MinDate}' that is not yyyy-MM-dd");
+            if (field.MaxDate != null && !IsDate(field.MaxDate))
+                errors.Add($"Field '{field.Name}' has max date '{field.MaxDate}' that is not yyyy-MM-dd");
+
+            foreach (var country in (field.Countries ?? Enumerable.Empty<string>()).Where(c => !FormCountries.IsKnown(c)))
+                errors.Add($"Field '{field.Name}' has unknown country '{country}'");
+
+            if (field.MaxSize != null && field.Type != "file")
+                errors.Add($"Field '{field.Name}' has a max size but is not a file field");
+            else if (field.MaxSize <= 0)
+                errors.Add($"Field '{field.Name}' has max size {field.MaxSize} that is not positive");
+
+            if (field.DefaultCountry != null && (!FormCountries.IsKnown(field.DefaultCountry) ||
+                field.Countries?.Contains(field.DefaultCountry, StringComparer.OrdinalIgnoreCase) == false))
+                errors.Add($"Field '{field.Name}' has default country '{field.DefaultCountry}' that it does not offer");
+        }
+
+        return errors;
+    }
+
+    // Space-separated autocomplete tokens such as "section-billing shipping postal-code"
+    private static bool IsAutocompleteHint(string value) =>
+        value.Split(' ', StringSplitOptions.RemoveEmptyEntries) is { Length: > 0 } tokens &&
+        tokens.All(t => t.All(c => char.IsAsciiLetterLower(c) || char.IsAsciiDigit(c) || c == '-'));
+
+    private static bool IsDate(string value) =>
+        DateOnly.TryParseExact(value, "yyyy-MM-dd", CultureInfo.InvariantCulture, DateTimeStyles.None, out _);
+
+    /// <summary>
+    /// Reads the masked field names recorded in journey data for each step (in-memory map or deserialized JSON object)
+    /// </summary>
+    public static Dictionary<string, HashSet<string>> GetSensitiveFieldsByStep(IDictionary<string, object>? journeyData)
+    {
+        var steps = new Dictionary<string, HashSet<string>>();
+
+        if (journeyData == null || !journeyData.TryGetValue(SensitiveFieldsDataKey, out var value))
+        {
+            return steps;
+        }
+
+        if (value is IEnumerable<KeyValuePair<string, List<string>>> recorded)
+        {
+            foreach (var (stepId, names) in recorded)
+            {
+                steps[stepId] = new HashSet<string>(names, StringComparer.OrdinalIgnoreCase);
+            }
+        }
+        else if (value is JsonElement { ValueKind: JsonValueKind.Object } element)
+        {
+            foreach (var step in element.EnumerateObject().Where(p => p.Value.ValueKind == JsonValueKind.Array))
+            {
+                steps[step.Name] = new HashSet<string>(step.Value.EnumerateArray()
+                    .Where(e => e.ValueKind == JsonValueKind.String)
+                    .Select(e => e.GetString()!), StringComparer.OrdinalIgnoreCase);
+            }
+        }
+
+        return steps;
+    }
+
+    /// <summary>
+    /// Masked field names declared by any step of the journey, for redacting and excluding values
+    /// </summary>
+    public static HashSet<string> GetSensitiveFields(IDictionary<string, object>? journeyData)
+    {
+        var fields = new HashSet<string>(StringComparer.OrdinalIgnoreCase);
+        foreach (var names in GetSensitiveFieldsByStep(journeyData).Values)
+        {
+            fields.UnionWith(names);
+        }
+
+        return fields;
+    }
+
+    /// <summary>
+    /// Masked field names declared by the forms of <paramref name="stepId"/>, whose values that step's plugin may receive
+    /// </summary>
+    public static HashSet<string> GetSensitiveFields(IDictionary<string, object>? journeyData, string stepId) =>
+        GetSensitiveFieldsByStep(journeyData).TryGetValue(stepId, out var fields)
+            ? fields
+            : new HashSet<string>(StringComparer.OrdinalIgnoreCase);
 }
 
 /// <summary>
@@ -341,10 +767,41 @@ public class DynamicFormField
     public string? Label { get; set; }
 
     /// <summary>
-    /// Field type: text, email, password, number, date, select, checkbox, radio, hidden, textarea
+    /// Field type: text, email, password, otp, masked_text, number, date, phone, country, select, checkbox, radio, hidden, textarea, file,
+    /// or a content block: markdown, alert, link
     /// </summary>
     public string Type { get; set; } = "text";
 
+    /// <summary>
+    /// Field types whose values are masked in the browser, never logged and never echoed back
+    /// </summary>
+    public static readonly IReadOnlySet<string> SensitiveTypes =
+        new HashSet<string>(StringComparer.OrdinalIgnoreCase) { "password", "otp", "masked_text" };
+
+    /// <summary>
+    /// Whether the field collects a secret (see <see cref="SensitiveTypes"/>)
+    /// </summary>
+    [JsonIgnore]
+    public bool IsSensitive => SensitiveTypes.Contains(Type);
+
+    /// <summary>
+    /// Non-input field types that show content (instructions, legal text, provisioning details)
+    /// </summary>
+    public static readonly IReadOnlySet<string> ContentTypes =
+        new HashSet<string>(StringComparer.OrdinalIgnoreCase) { "markdown", "alert", "link" };
+
+    /// <summary>
+    /// Alert styles: info, success, warning, danger
+    /// </summary>
+    public static readonly IReadOnlySet<string> AlertVariants =
+        new HashSet<string>(StringComparer.OrdinalIgnoreCase) { "info", "success", "warning", "danger" };
+
+    /// <summary>
+    /// Whether the field is a content block (see <see cref="ContentTypes"/>); content blocks need no name and submit nothing
+    /// </summary>
+    [JsonIgnore]
+    public bool IsContent => ContentTypes.Contains(Type);
+
     /// <summary>
     /// Placeholder text
     /// </summary>
@@ -365,6 +822,34 @@ public class DynamicFormField
     /// </summary>
     public string? HelpText { get; set; }
 
+    /// <summary>
+    /// Accessible name announced by screen readers instead of the visible label
+    /// </summary>
+    public string? AriaLabel { get; set; }
+
+    /// <summary>
+    /// Longer description announced by screen readers but not shown (aria-describedby)
+    /// </summary>
+    public string? AriaDescription { get; set; }
+
+    /// <summary>
+    /// HTML autocomplete hint (e.g. "given-name", "bday", "shipping postal-code"), overriding the type's default
+    /// </summary>
+    public string? Autocomplete { get; set; }
+
+    /// <summary>
+    /// Virtual keyboard hint (see <see cref="InputModes"/>), overriding the type's default
+    /// </summary>
+    public string? InputMode { get; set; }
+
+    /// <summary>
+    /// Supported inputmode values
+    /// </summary>
+    public static readonly IReadOnlySet<string> InputModes = new HashSet<string>(StringComparer.OrdinalIgnoreCase)
+    {
+        "none", "text", "decimal", "numeric", "tel", "search", "email", "url"
+    };
+
     /// <summary>
     /// Validation error message for this field
     /// </summary>
@@ -390,10 +875,648 @@ public class DynamicFormField
     /// </summary>
     public int? Max { get; set; }
 
+    /// <summary>
+    /// Visible rows for textarea fields
+    /// </summary>
+    public int? Rows { get; set; }
+
+    /// <summary>
+    /// Accepted content types for file fields (e.g. "image/*,application/pdf")
+    /// </summary>
+    public string? Accept { get; set; }
+
+    /// <summary>
+    /// Largest upload accepted by a file field, in bytes; the attachment store's limit applies regardless
+    /// </summary>
+    public long? MaxSize { get; set; }
+
+    /// <summary>
+    /// Markdown of a markdown or alert content block (sanitized by the executor before rendering)
+    /// </summary>
+    public string? Content { get; set; }
+
+    /// <summary>
+    /// Style of an alert content block (see <see cref="AlertVariants"/>), defaults to info
+    /// </summary>
+    public string? Variant { get; set; }
+
+    /// <summary>
+    /// Target of a link content block; the label is the link text
+    /// </summary>
+    public string? Href { get; set; }
+
+    /// <summary>
+    /// Earliest date accepted by a date field (yyyy-MM-dd)
+    /// </summary>
+    public string? MinDate { get; set; }
+
+    /// <summary>
+    /// Latest date accepted by a date field (yyyy-MM-dd)
+    /// </summary>
+    public string? MaxDate { get; set; }
+
+    /// <summary>
+    /// Country preselected in a phone or country field (ISO 3166-1 alpha-2)
+    /// </summary>
+    public string? DefaultCountry { get; set; }
+
+    /// <summary>
+    /// Countries offered by a phone or country field (ISO 3166-1 alpha-2); all countries when null.
+    /// A phone field submits the selected country as <c>{name}_country</c>.
+    /// </summary>
+    public List<string>? Countries { get; set; }
+
+    /// <summary>
+    /// Declarative validation rules enforced by the browser before submission
+    /// (the PDK applies the same rules to the submitted input)
+    /// </summary>
+    public List<DynamicFormValidationRule>? Validation { get; set; }
+
+    /// <summary>
+    /// Plugin function that supplies the options of a select field on demand, called with the
+    /// partial form state (and the search text in <c>__query</c>) instead of listing <see cref="Options"/> upfront
+    /// </summary>
+    public string? OptionsFunction { get; set; }
+
+    /// <summary>
+    /// Show the field only while another field's value matches (e.g. company when account_type equals business).
+    /// Hidden fields are not submitted and not required.
+    /// </summary>
+    public DynamicFormCondition? ShowIf { get; set; }
+
     /// <summary>
     /// Custom CSS class for this field
     /// </summary>
     public string? CssClass { get; set; }
+
+    /// <summary>
+    /// Returns why a content block is malformed, or null if it is valid
+    /// </summary>
+    internal string? ValidateContent()
+    {
+        switch (Type.ToLowerInvariant())
+        {
+            case "link":
+                if (string.IsNullOrWhiteSpace(Label))
+                    return "needs a label";
+                return PluginContentSanitizer.IsAllowedUrl(Href) ? null : $"has unsupported URL '{Href}'";
+            case "alert" when Variant != null && !AlertVariants.Contains(Variant):
+                return $"has unsupported variant '{Variant}'";
+            default:
+                if (string.IsNullOrWhiteSpace(Content))
+                    return "has no content";
+                return Content.Length > PluginContentSanitizer.MaxContentLength
+                    ? $"exceeds {PluginContentSanitizer.MaxContentLength} characters"
+                    : null;
+        }
+    }
+}
+
+/// <summary>
+/// Theming hints a plugin attaches to its form screen
+/// </summary>
+public class DynamicFormBranding
+{
+    /// <summary>
+    /// Journey layouts a form can ask for (see <see cref="JourneyLayouts"/>)
+    /// </summary>
+    public static readonly IReadOnlySet<string> Layouts = new HashSet<string>(StringComparer.OrdinalIgnoreCase)
+    {
+        JourneyLayouts.Narrow, JourneyLayouts.Medium, JourneyLayouts.Wide, JourneyLayouts.Full
+    };
+
+    /// <summary>
+    /// Reference of a logo from the tenant's plugin branding (not a URL), replacing the journey's logo
+    /// </summary>
+    public string? Logo { get; set; }
+
+    /// <summary>
+    /// Accent color as hex (#rrggbb), replacing the journey's primary color
+    /// </summary>
+    public string? AccentColor { get; set; }
+
+    /// <summary>
+    /// Layout width of the screen (see <see cref="Layouts"/>)
+    /// </summary>
+    public string? Layout { get; set; }
+
+    /// <summary>
+    /// Returns why hints are malformed, regardless of what the tenant allows
+    /// </summary>
+    internal IEnumerable<string> Validate()
+    {
+        if (AccentColor != null && !IsHexColor(AccentColor))
+            yield return $"Branding accent color '{AccentColor}' is not a #rrggbb color";
+        if (Layout != null && !Layouts.Contains(Layout))
+            yield return $"Branding layout '{Layout}' is not supported";
+    }
+
+    /// <summary>
+    /// Keeps only the hints the tenant allows, resolving the logo reference to its URL.
+    /// Hints that are not allowed are listed in <paramref name="rejected"/>.
+    /// </summary>
+    public ResolvedFormBranding Resolve(PluginBrandingSettings allowed, out IReadOnlyList<string> rejected)
+    {
+        var rejectedHints = new List<string>();
+        var resolved = new ResolvedFormBranding();
+
+        if (Logo != null)
+        {
+            if (allowed.Logos.TryGetValue(Logo, out var logoUrl))
+                resolved.LogoUrl = logoUrl;
+            else
+                rejectedHints.Add($"logo '{Logo}'");
+        }
+
+        if (AccentColor != null)
+        {
+            if (allowed.AccentColors.Contains(AccentColor, StringComparer.OrdinalIgnoreCase))
+                resolved.AccentColor = AccentColor.ToLowerInvariant();
+            else
+                rejectedHints.Add($"accent color '{AccentColor}'");
+        }
+
+        if (Layout != null)
+        {
+            if (allowed.Layouts.Count == 0 || allowed.Layouts.Contains(Layout, StringComparer.OrdinalIgnoreCase))
+                resolved.Layout = Layout.ToLowerInvariant();
+            else
+                rejectedHints.Add($"layout '{Layout}'");
+        }
+
+        rejected = rejectedHints;
+        return resolved;
+    }
+
+    private static bool IsHexColor(string value) =>
+        value.Length == 7 && value[0] == '#' && value.Skip(1).All(char.IsAsciiHexDigit);
+}
+
+/// <summary>
+/// Branding hints that passed the tenant's plugin branding policy
+/// </summary>
+public class ResolvedFormBranding
+{
+    public string? LogoUrl { get; set; }
+    public string? AccentColor { get; set; }
+    public string? Layout { get; set; }
+}
+
+/// <summary>
+/// Multi-page structure of a plugin form: the pages of the wizard and which one the form shows
+/// </summary>
+public class DynamicFormWizard
+{
+    /// <summary>
+    /// The user can go back to earlier pages (default)
+    /// </summary>
+    public const string BackAllowed = "allowed";
+
+    /// <summary>
+    /// Earlier pages cannot be revisited, e.g. after a code was sent
+    /// </summary>
+    public const string BackDisabled = "disabled";
+
+    /// <summary>
+    /// Submitted (instead of the form's fields being validated) when the user goes back a page
+    /// </summary>
+    public const string BackInputName = "__wizard_back";
+
+    public static readonly IReadOnlySet<string> BackNavigationPolicies =
+        new HashSet<string>(StringComparer.OrdinalIgnoreCase) { BackAllowed, BackDisabled };
+
+    /// <summary>
+    /// Pages in order
+    /// </summary>
+    public List<DynamicFormWizardPage> Pages { get; set; } = new();
+
+    /// <summary>
+    /// Id of the page this form shows
+    /// </summary>
+    public string CurrentPage { get; set; } = null!;
+
+    /// <summary>
+    /// Completion percentage (0-100); defaults to the share of pages before the current one
+    /// </summary>
+    public int? Progress { get; set; }
+
+    /// <summary>
+    /// Back navigation policy: allowed or disabled
+    /// </summary>
+    public string BackNavigation { get; set; } = BackAllowed;
+
+    /// <summary>
+    /// Position of the current page, or -1 if it is not one of the pages
+    /// </summary>
+    [JsonIgnore]
+    public int CurrentIndex => Pages.FindIndex(p => string.Equals(p.Id, CurrentPage, StringComparison.OrdinalIgnoreCase));
+
+    /// <summary>
+    /// Whether the form shows a back button
+    /// </summary>
+    [JsonIgnore]
+    public bool CanGoBack => CurrentIndex > 0 && string.Equals(BackNavigation, BackAllowed, StringComparison.OrdinalIgnoreCase);
+
+    /// <summary>
+    /// Progress to display, explicit or derived from the current page
+    /// </summary>
+    public int GetProgress() => Progress ?? (Pages.Count > 0 ? Math.Max(CurrentIndex, 0) * 100 / Pages.Count : 0);
+
+    internal IEnumerable<string> Validate()
+    {
+        if (Pages.Count == 0)
+            yield return "Wizard has no pages";
+
+        foreach (var page in Pages.Where(p => string.IsNullOrWhiteSpace(p.Id) || string.IsNullOrWhiteSpace(p.Title)))
+            yield return $"Wizard page {Pages.IndexOf(page) + 1} needs an id and a title";
+
+        foreach (var id in Pages.Where(p => !string.IsNullOrWhiteSpace(p.Id))
+                     .GroupBy(p => p.Id, StringComparer.OrdinalIgnoreCase).Where(g => g.Count() > 1).Select(g => g.Key))
+            yield return $"Wizard page id '{id}' is used more than once";
+
+        if (Pages.Count > 0 && CurrentIndex < 0)
+            yield return $"Wizard current page '{CurrentPage}' is not one of its pages";
+
+        if (Progress is < 0 or > 100)
+            yield return $"Wizard progress {Progress} is not between 0 and 100";
+
+        if (!BackNavigationPolicies.Contains(BackNavigation))
+            yield return $"Wizard back navigation '{BackNavigation}' is not supported";
+    }
+}
+
+/// <summary>
+/// A page of a plugin form wizard
+/// </summary>
+public class DynamicFormWizardPage
+{
+    public string Id { get; set; } = null!;
+    public string Title { get; set; } = null!;
+}
+
+/// <summary>
+/// Validation rule of a dynamic form field
+/// </summary>
+public class DynamicFormValidationRule
+{
+    /// <summary>
+    /// Rule type: pattern, min_length, max_length, min, max, required_if
+    /// </summary>
+    public string Type { get; set; } = null!;
+
+    /// <summary>
+    /// Regex (anchored to the whole value) for pattern, a non-negative whole number for min_length and
+    /// max_length, a number for min and max
+    /// </summary>
+    public JsonElement? Value { get; set; }
+
+    /// <summary>
+    /// Error shown when the rule fails
+    /// </summary>
+    public string? Message { get; set; }
+
+    /// <summary>
+    /// Condition under which a required_if field is required
+    /// </summary>
+    public DynamicFormCondition? Condition { get; set; }
+
+    /// <summary>
+    /// Returns why the rule is malformed, or null if it is valid
+    /// </summary>
+    internal string? Validate(IReadOnlySet<string> fieldNames)
+    {
+        switch (Type)
+        {
+            case "pattern":
+                if (Value is not { ValueKind: JsonValueKind.String } pattern)
+                    return "value must be a regex";
+                try
+                {
+                    _ = new System.Text.RegularExpressions.Regex(pattern.GetString()!);
+                    return null;
+                }
+                catch (ArgumentException ex)
+                {
+                    return ex.Message;
+                }
+
+            case "min_length" or "max_length":
+                return Value is { ValueKind: JsonValueKind.Number } length && length.TryGetInt32(out var count) && count >= 0
+                    ? null
+                    : "value must be a non-negative whole number";
+
+            case "min" or "max":
+                return Value is { ValueKind: JsonValueKind.Number } ? null : "value must be a number";
+
+            case "required_if":
+                if (Condition == null)
+                    return "condition is missing";
+                if (!fieldNames.Contains(Condition.Field))
+                    return $"condition references unknown field '{Condition.Field}'";
+                return DynamicFormCondition.Operators.Contains(Condition.Operator)
+                    ? null
+                    : $"unsupported operator '{Condition.Operator}'";
+
+            default:
+                return "unsupported rule type";
+        }
+    }
+}
+
+/// <summary>
+/// Plugin function that supplies a select field's options, recorded in journey data while the form is shown
+/// </summary>
+public class PluginFieldOptionsSource
+{
+    private static readonly JsonSerializerOptions JsonOptions = new()
+    {
+        PropertyNameCaseInsensitive = true
+    };
+
+    /// <summary>
+    /// Journey data key holding the sources of the current form, keyed by field name
+    /// </summary>
+    public const string JourneyDataKey = "pluginFieldOptions";
+
+    public required string PluginName { get; init; }
+    public required string FunctionName { get; init; }
+
+    /// <summary>
+    /// Step that showed the form; its plugin configuration applies to the options call
+    /// </summary>
+    public required string StepId { get; init; }
+
+    /// <summary>
+    /// Finds the source of <paramref name="fieldName"/> in the form the journey is showing. Sources are
+    /// only returned while the journey is in progress and still on the step that showed the form.
+    /// </summary>
+    public static PluginFieldOptionsSource? Find(JourneyState state, string fieldName)
+    {
+        if (state.Status != JourneyStatus.InProgress ||
+            !FromJourneyData(state.Data).TryGetValue(fieldName, out var source) ||
+            source.StepId != state.CurrentStepId)
+        {
+            return null;
+        }
+
+        return source;
+    }
+
+    /// <summary>
+    /// Reads the recorded sources from journey data (in-memory dictionary or deserialized JSON object)
+    /// </summary>
+    public static Dictionary<string, PluginFieldOptionsSource> FromJourneyData(IDictionary<string, object>? journeyData)
+    {
+        if (journeyData == null || !journeyData.TryGetValue(JourneyDataKey, out var value))
+        {
+            return new Dictionary<string, PluginFieldOptionsSource>();
+        }
+
+        return value switch
+        {
+            IDictionary<string, PluginFieldOptionsSource> sources => new Dictionary<string, PluginFieldOptionsSource>(sources),
+            JsonElement { ValueKind: JsonValueKind.Object } element =>
+                element.Deserialize<Dictionary<string, PluginFieldOptionsSource>>(JsonOptions)
+                    ?? new Dictionary<string, PluginFieldOptionsSource>(),
+            _ => new Dictionary<string, PluginFieldOptionsSource>()
+        };
+    }
+}
+
+/// <summary>
+/// Upload rules of a file field in the form currently shown, kept in journey data so the journey
+/// page only stores files the form asked for
+/// </summary>
+public class PluginFileField
+{
+    private static readonly JsonSerializerOptions JsonOptions = new()
+    {
+        PropertyNameCaseInsensitive = true
+    };
+
+    /// <summary>
+    /// Journey data key holding the file fields of the current form, keyed by field name
+    /// </summary>
+    public const string JourneyDataKey = "pluginFileFields";
+
+    /// <summary>
+    /// Field label, used in validation messages
+    /// </summary>
+    public required string Label { get; init; }
+
+    /// <summary>
+    /// Accepted content types and file extensions (e.g. "image/*,application/pdf,.heic"); null accepts any file
+    /// </summary>
+    public string? Accept { get; init; }
+
+    /// <summary>
+    /// Largest file accepted, in bytes; null leaves only the attachment store's limit
+    /// </summary>
+    public long? MaxSize { get; init; }
+
+    public static PluginFileField FromField(DynamicFormField field) => new()
+    {
+        Label = field.Label ?? field.Name,
+        Accept = field.Accept,
+        MaxSize = field.MaxSize
+    };
+
+    /// <summary>
+    /// Checks an upload against the field's rules and the attachment store's size limit
+    /// </summary>
+    /// <returns>A message for the user, or null if the file is accepted</returns>
+    public string? Validate(string fileName, string contentType, long length, long storeMaxSize)
+    {
+        var maxSize = MaxSize.HasValue ? Math.Min(MaxSize.Value, storeMaxSize) : storeMaxSize;
+        if (length > maxSize)
+            return $"{Label} must be {FormatSize(maxSize)} or smaller";
+
+        if (!Accepts(fileName, contentType))
+            return $"{Label} must be one of these file types: {Accept}";
+
+        return null;
+    }
+
+    /// <summary>
+    /// Whether the file matches <see cref="Accept"/>: an exact content type, a wildcard such as
+    /// image/* or a file extension such as .pdf
+    /// </summary>
+    public bool Accepts(string fileName, string contentType)
+    {
+        if (string.IsNullOrWhiteSpace(Accept))
+            return true;
+
+        return Accept.Split(',', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries).Any(accepted =>
+            accepted.StartsWith('.')
+                ? fileName.EndsWith(accepted, StringComparison.OrdinalIgnoreCase)
+                : accepted.EndsWith("/*")
+                    ? contentType.StartsWith(accepted[..^1], StringComparison.OrdinalIgnoreCase)
+                    : string.Equals(accepted, contentType, StringComparison.OrdinalIgnoreCase));
+    }
+
+    private static string FormatSize(long bytes) => bytes switch
+    {
+        >= 1024 * 1024 => $"{bytes / (1024.0 * 1024):0.#} MB",
+        >= 1024 => $"{bytes / 1024.0:0.#} KB",
+        _ => $"{bytes} bytes"
+    };
+
+    /// <summary>
+    /// Reads the recorded file fields from journey data (in-memory dictionary or deserialized JSON object)
+    /// </summary>
+    public static Dictionary<string, PluginFileField> FromJourneyData(IDictionary<string, object>? journeyData)
+    {
+        if (journeyData == null || !journeyData.TryGetValue(JourneyDataKey, out var value))
+        {
+            return new Dictionary<string, PluginFileField>();
+        }
+
+        return value switch
+        {
+            IDictionary<string, PluginFileField> fields => new Dictionary<string, PluginFileField>(fields),
+            JsonElement { ValueKind: JsonValueKind.Object } element =>
+                element.Deserialize<Dictionary<string, PluginFileField>>(JsonOptions)
+                    ?? new Dictionary<string, PluginFileField>(),
+            _ => new Dictionary<string, PluginFileField>()
+        };
+    }
+}
+
+/// <summary>
+/// Position of a plugin step in its form wizard, kept in journey data between submissions so the
+/// plugin can tell which page was submitted and whether the user went back
+/// </summary>
+public class PluginWizardState
+{
+    private static readonly JsonSerializerOptions JsonOptions = new()
+    {
+        PropertyNameCaseInsensitive = true
+    };
+
+    /// <summary>
+    /// Journey data key holding the wizard state of each plugin step, keyed by step id
+    /// </summary>
+    public const string JourneyDataKey = "pluginWizard";
+
+    /// <summary>
+    /// Page shown last
+    /// </summary>
+    [JsonPropertyName("page")]
+    public string Page { get; set; } = null!;
+
+    /// <summary>
+    /// Pages submitted so far, oldest first; the last one is the page before <see cref="Page"/>
+    /// </summary>
+    [JsonPropertyName("history")]
+    public List<string> History { get; set; } = new();
+
+    /// <summary>
+    /// Answers submitted on earlier pages, without masked or sensitive fields
+    /// </summary>
+    [JsonPropertyName("values")]
+    public Dictionary<string, object> Values { get; set; } = new();
+
+    /// <summary>
+    /// Whether the plugin is called with a submission of <see cref="Page"/> (false when the page is only shown again)
+    /// </summary>
+    [JsonPropertyName("submitted")]
+    public bool Submitted { get; set; }
+
+    /// <summary>
+    /// Whether the submission asked to go back a page
+    /// </summary>
+    [JsonPropertyName("back")]
+    public bool Back { get; set; }
+
+    /// <summary>
+    /// Back navigation policy of the page shown last
+    /// </summary>
+    [JsonPropertyName("backNavigation")]
+    public string BackNavigation { get; set; } = DynamicFormWizard.BackAllowed;
+
+    /// <summary>
+    /// Records a submission of <see cref="Page"/>. A back request (honoured only if the page allowed it)
+    /// leaves the answers alone; otherwise the page joins the history and its answers are kept.
+    /// </summary>
+    public void Submit(IDictionary<string, object>? input, IReadOnlyCollection<string> sensitiveFields)
+    {
+        Submitted = true;
+        Back = input?.ContainsKey(DynamicFormWizard.BackInputName) == true && History.Count > 0 &&
+            string.Equals(BackNavigation, DynamicFormWizard.BackAllowed, StringComparison.OrdinalIgnoreCase);
+        if (Back)
+        {
+            return;
+        }
+
+        History.Add(Page);
+        foreach (var (key, value) in PluginInputSanitizer.SanitizeExcluding(input, sensitiveFields) ?? new Dictionary<string, object>())
+        {
+            if (!key.StartsWith("__"))
+                Values[key] = value;
+        }
+    }
+
+    /// <summary>
+    /// Records that <paramref name="wizard"/>'s current page is shown. Showing a page again (going back,
+    /// or a page that failed validation) drops it and every later page from the history.
+    /// </summary>
+    public void Show(DynamicFormWizard wizard)
+    {
+        var index = History.FindIndex(p => string.Equals(p, wizard.CurrentPage, StringComparison.OrdinalIgnoreCase));
+        if (index >= 0)
+        {
+            History.RemoveRange(index, History.Count - index);
+        }
+
+        Page = wizard.CurrentPage;
+        BackNavigation = wizard.BackNavigation;
+        Submitted = false;
+        Back = false;
+    }
+
+    /// <summary>
+    /// Reads the wizard states from journey data (in-memory dictionary or deserialized JSON object)
+    /// </summary>
+    public static Dictionary<string, PluginWizardState> FromJourneyData(IDictionary<string, object>? journeyData)
+    {
+        if (journeyData == null || !journeyData.TryGetValue(JourneyDataKey, out var value))
+        {
+            return new Dictionary<string, PluginWizardState>();
+        }
+
+        return value switch
+        {
+            IDictionary<string, PluginWizardState> states => new Dictionary<string, PluginWizardState>(states),
+            JsonElement { ValueKind: JsonValueKind.Object } element =>
+                element.Deserialize<Dictionary<string, PluginWizardState>>(JsonOptions)
+                    ?? new Dictionary<string, PluginWizardState>(),
+            _ => new Dictionary<string, PluginWizardState>()
+        };
+    }
+}
+
+/// <summary>
+/// Visibility condition of a dynamic form field
+/// </summary>
+public class DynamicFormCondition
+{
+    /// <summary>
+    /// Supported operators: equals, not_equals, empty, not_empty
+    /// </summary>
+    public static readonly IReadOnlySet<string> Operators =
+        new HashSet<string>(StringComparer.OrdinalIgnoreCase) { "equals", "not_equals", "empty", "not_empty" };
+
+    /// <summary>
+    /// Name of the field whose value is tested
+    /// </summary>
+    public string Field { get; set; } = null!;
+
+    public string Operator { get; set; } = "equals";
+
+    /// <summary>
+    /// Value compared against (ignored for empty/not_empty)
+    /// </summary>
+    public string? Value { get; set; }
 }
 
 /// <summary>
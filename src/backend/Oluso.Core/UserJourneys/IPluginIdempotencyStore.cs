@@ -0,0 +1,18 @@
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Cache of completed plugin results keyed by idempotency key, used by the executor
+/// to suppress retried invocations of side-effecting plugins
+/// </summary>
+public interface IPluginIdempotencyStore
+{
+    /// <summary>
+    /// Gets the cached result for a key, if it has not expired
+    /// </summary>
+    Task<PluginExecutionResult?> GetAsync(string key, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Caches a result for a key
+    /// </summary>
+    Task SetAsync(string key, PluginExecutionResult result, TimeSpan expiration, CancellationToken cancellationToken = default);
+}
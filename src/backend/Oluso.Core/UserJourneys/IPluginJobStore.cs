@@ -0,0 +1,139 @@
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Store for long-running plugin job state (checkpoints and progress).
+/// Register a durable implementation so jobs can resume after the executor restarts.
+/// </summary>
+public interface IPluginJobStore
+{
+    /// <summary>
+    /// Gets a job by ID
+    /// </summary>
+    Task<PluginJobState?> GetAsync(string jobId, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Saves a job
+    /// </summary>
+    Task SaveAsync(PluginJobState job, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Deletes a job
+    /// </summary>
+    Task DeleteAsync(string jobId, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Gets every job that is still running, including jobs interrupted by a restart
+    /// </summary>
+    Task<IReadOnlyList<PluginJobState>> GetRunningAsync(CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Claims a job for <paramref name="owner"/> for <paramref name="leaseDuration"/>, so only one of the
+    /// processors sharing the store runs it. Succeeds if nobody holds the job, the holder's lease has expired,
+    /// or <paramref name="owner"/> holds it already (which renews the lease).
+    /// </summary>
+    Task<bool> TryClaimAsync(string jobId, string owner, TimeSpan leaseDuration, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Gives up the claim of <paramref name="owner"/> on a job, if it still holds it
+    /// </summary>
+    Task ReleaseAsync(string jobId, string owner, CancellationToken cancellationToken = default);
+}
+
+/// <summary>
+/// State of a long-running plugin job
+/// </summary>
+public class PluginJobState
+{
+    /// <summary>
+    /// Step output key holding the ID of the job a custom_plugin step queued
+    /// </summary>
+    public const string JourneyDataKey = "pluginJobId";
+
+    /// <summary>
+    /// Unique job ID chosen by the caller
+    /// </summary>
+    public required string JobId { get; init; }
+
+    /// <summary>
+    /// Plugin running the job
+    /// </summary>
+    public required string PluginName { get; init; }
+
+    /// <summary>
+    /// Plugin function invoked for each batch
+    /// </summary>
+    public required string FunctionName { get; init; }
+
+    /// <summary>
+    /// Tenant ID (if multi-tenant)
+    /// </summary>
+    public string? TenantId { get; init; }
+
+    /// <summary>
+    /// User the job runs for
+    /// </summary>
+    public string? UserId { get; init; }
+
+    /// <summary>
+    /// Journey that started the job; scopes the messages the job publishes
+    /// </summary>
+    public string? JourneyId { get; init; }
+
+    /// <summary>
+    /// Journey step that started the job
+    /// </summary>
+    public string? StepId { get; init; }
+
+    /// <summary>
+    /// Input every invocation receives, without sensitive fields
+    /// </summary>
+    public IDictionary<string, object>? Input { get; init; }
+
+    /// <summary>
+    /// Step configuration overrides, resolved against the platform and tenant layers when the job runs
+    /// </summary>
+    public IDictionary<string, object>? Config { get; init; }
+
+    /// <summary>
+    /// Current status
+    /// </summary>
+    public PluginJobStatus Status { get; set; } = PluginJobStatus.Running;
+
+    /// <summary>
+    /// Last reported progress (0-100)
+    /// </summary>
+    public int ProgressPercent { get; set; }
+
+    /// <summary>
+    /// Last reported progress message
+    /// </summary>
+    public string? ProgressMessage { get; set; }
+
+    /// <summary>
+    /// Checkpoint (JSON) returned by the last completed invocation
+    /// </summary>
+    public string? Checkpoint { get; set; }
+
+    /// <summary>
+    /// Number of invocations so far
+    /// </summary>
+    public int Invocations { get; set; }
+
+    /// <summary>
+    /// Error message if the job failed
+    /// </summary>
+    public string? Error { get; set; }
+
+    public DateTime CreatedAt { get; init; } = DateTime.UtcNow;
+    public DateTime UpdatedAt { get; set; } = DateTime.UtcNow;
+}
+
+/// <summary>
+/// Status of a long-running plugin job
+/// </summary>
+public enum PluginJobStatus
+{
+    Running,
+    Completed,
+    Failed
+}
@@ -0,0 +1,71 @@
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Journey-scoped message bus that lets cooperating plugins exchange data
+/// (e.g. a risk collector publishing a score for an MFA decision plugin)
+/// without sharing journey data key conventions.
+/// </summary>
+public interface IPluginMessageBus
+{
+    /// <summary>
+    /// Publishes a message on a topic within a journey
+    /// </summary>
+    Task PublishAsync(string journeyId, PluginMessage message, CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Publishes messages returned by a managed plugin, attributed to <paramref name="publisher"/>
+    /// </summary>
+    async Task PublishAllAsync(
+        string journeyId,
+        string publisher,
+        IEnumerable<PluginMessage>? messages,
+        CancellationToken cancellationToken = default)
+    {
+        foreach (var message in messages ?? Enumerable.Empty<PluginMessage>())
+        {
+            await PublishAsync(journeyId, new PluginMessage
+            {
+                Topic = message.Topic,
+                Payload = message.Payload,
+                Publisher = publisher,
+                PublishedAt = message.PublishedAt
+            }, cancellationToken);
+        }
+    }
+
+    /// <summary>
+    /// Gets the messages published in a journey on the given topics, oldest first
+    /// </summary>
+    Task<IReadOnlyDictionary<string, IReadOnlyList<PluginMessage>>> GetMessagesAsync(
+        string journeyId,
+        IEnumerable<string> topics,
+        CancellationToken cancellationToken = default);
+
+    /// <summary>
+    /// Removes all messages for a journey
+    /// </summary>
+    Task ClearAsync(string journeyId, CancellationToken cancellationToken = default);
+}
+
+/// <summary>
+/// A message published by a plugin
+/// </summary>
+public class PluginMessage
+{
+    /// <summary>
+    /// Topic the message was published on (e.g., "risk.score")
+    /// </summary>
+    public required string Topic { get; init; }
+
+    /// <summary>
+    /// Message payload
+    /// </summary>
+    public object? Payload { get; init; }
+
+    /// <summary>
+    /// Name of the publishing plugin
+    /// </summary>
+    public string? Publisher { get; init; }
+
+    public DateTime PublishedAt { get; init; } = DateTime.UtcNow;
+}
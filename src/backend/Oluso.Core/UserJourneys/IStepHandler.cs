@@ -412,12 +412,16 @@ public class StepHandlerResult
         new() { Outcome = StepOutcome.RequireInput, StepResult = stepResult };
 
     /// <summary>
-    /// Show a view with the specified view model
+    /// Show a view with the specified view model, optionally saving data to the journey while it waits
     /// </summary>
-    public static StepHandlerResult ShowUi(string viewName, object? viewModel = null) =>
+    public static StepHandlerResult ShowUi(
+        string viewName,
+        object? viewModel = null,
+        IDictionary<string, object>? outputData = null) =>
         new()
         {
             Outcome = StepOutcome.RequireInput,
+            OutputData = outputData,
             StepResult = new JourneyStepResult
             {
                 StepId = "",
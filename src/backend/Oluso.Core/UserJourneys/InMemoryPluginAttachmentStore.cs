@@ -0,0 +1,94 @@
+using System.Collections.Concurrent;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// In-memory implementation of IPluginAttachmentStore.
+/// Attachments are dropped once they are older than the retention period.
+/// </summary>
+public class InMemoryPluginAttachmentStore : IPluginAttachmentStore
+{
+    private readonly ConcurrentDictionary<string, (PluginAttachment Attachment, byte[] Content)> _attachments = new();
+    private readonly TimeSpan _retention;
+
+    public InMemoryPluginAttachmentStore(long maxSizeBytes = 10 * 1024 * 1024, TimeSpan? retention = null)
+    {
+        MaxSizeBytes = maxSizeBytes;
+        _retention = retention ?? TimeSpan.FromHours(1);
+    }
+
+    public long MaxSizeBytes { get; }
+
+    public async Task<PluginAttachment> StoreAsync(
+        string journeyId,
+        string fileName,
+        string contentType,
+        Stream content,
+        CancellationToken cancellationToken = default)
+    {
+        using var buffer = new MemoryStream();
+        await content.CopyToAsync(buffer, cancellationToken);
+
+        if (buffer.Length > MaxSizeBytes)
+        {
+            throw new InvalidOperationException($"Attachment exceeds the maximum size of {MaxSizeBytes} bytes");
+        }
+
+        var attachment = new PluginAttachment
+        {
+            Id = Guid.NewGuid().ToString("N"),
+            JourneyId = journeyId,
+            FileName = fileName,
+            ContentType = contentType,
+            Size = buffer.Length
+        };
+        _attachments[attachment.Id] = (attachment, buffer.ToArray());
+
+        RemoveExpired();
+        return attachment;
+    }
+
+    public Task<PluginAttachment?> GetAsync(string attachmentId, CancellationToken cancellationToken = default)
+    {
+        return Task.FromResult(_attachments.TryGetValue(attachmentId, out var entry) ? entry.Attachment : null);
+    }
+
+    public Task<byte[]?> ReadAsync(string attachmentId, CancellationToken cancellationToken = default)
+    {
+        return Task.FromResult(_attachments.TryGetValue(attachmentId, out var entry) ? entry.Content : null);
+    }
+
+    public Task<IReadOnlyList<PluginAttachment>> ListAsync(string journeyId, CancellationToken cancellationToken = default)
+    {
+        IReadOnlyList<PluginAttachment> attachments = _attachments.Values
+            .Select(e => e.Attachment)
+            .Where(a => a.JourneyId == journeyId)
+            .ToList();
+        return Task.FromResult(attachments);
+    }
+
+    public Task ClearAsync(string journeyId, CancellationToken cancellationToken = default)
+    {
+        foreach (var (id, entry) in _attachments)
+        {
+            if (entry.Attachment.JourneyId == journeyId)
+            {
+                _attachments.TryRemove(id, out _);
+            }
+        }
+
+        return Task.CompletedTask;
+    }
+
+    private void RemoveExpired()
+    {
+        var cutoff = DateTime.UtcNow - _retention;
+        foreach (var (id, entry) in _attachments)
+        {
+            if (entry.Attachment.UploadedAt < cutoff)
+            {
+                _attachments.TryRemove(id, out _);
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+using System.Collections.Concurrent;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// In-memory implementation of IPluginIdempotencyStore for single-server deployments.
+/// Expired results are dropped when read, and swept periodically so results that are never read again do not pile up.
+/// </summary>
+public class InMemoryPluginIdempotencyStore : IPluginIdempotencyStore, IDisposable
+{
+    private readonly ConcurrentDictionary<string, (PluginExecutionResult Result, DateTime ExpiresAt)> _results = new();
+    private readonly Timer _sweepTimer;
+
+    public InMemoryPluginIdempotencyStore()
+        : this(TimeSpan.FromMinutes(1))
+    {
+    }
+
+    /// <param name="sweepInterval">How often expired results are removed</param>
+    public InMemoryPluginIdempotencyStore(TimeSpan sweepInterval)
+    {
+        _sweepTimer = new Timer(_ => RemoveExpired(), null, sweepInterval, sweepInterval);
+    }
+
+    public Task<PluginExecutionResult?> GetAsync(string key, CancellationToken cancellationToken = default)
+    {
+        if (_results.TryGetValue(key, out var entry))
+        {
+            if (entry.ExpiresAt > DateTime.UtcNow)
+            {
+                return Task.FromResult<PluginExecutionResult?>(entry.Result);
+            }
+
+            _results.TryRemove(KeyValuePair.Create(key, entry));
+        }
+
+        return Task.FromResult<PluginExecutionResult?>(null);
+    }
+
+    public Task SetAsync(string key, PluginExecutionResult result, TimeSpan expiration, CancellationToken cancellationToken = default)
+    {
+        _results[key] = (result, DateTime.UtcNow + expiration);
+        return Task.CompletedTask;
+    }
+
+    /// <summary>
+    /// Removes every expired result
+    /// </summary>
+    public void RemoveExpired()
+    {
+        var now = DateTime.UtcNow;
+        foreach (var entry in _results)
+        {
+            if (entry.Value.ExpiresAt <= now)
+            {
+                _results.TryRemove(entry);
+            }
+        }
+    }
+
+    /// <summary>
+    /// Number of results held, including expired ones not yet removed
+    /// </summary>
+    public int Count => _results.Count;
+
+    public void Dispose()
+    {
+        _sweepTimer.Dispose();
+    }
+}
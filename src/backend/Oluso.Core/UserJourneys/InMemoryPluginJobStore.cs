@@ -0,0 +1,75 @@
+using System.Collections.Concurrent;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// In-memory implementation of IPluginJobStore for development/testing.
+/// Jobs survive plugin reloads but not process restarts.
+/// </summary>
+public class InMemoryPluginJobStore : IPluginJobStore
+{
+    private readonly ConcurrentDictionary<string, PluginJobState> _jobs = new();
+
+    /// <summary>
+    /// Holder and expiry of each claimed job; guarded by locking the dictionary
+    /// </summary>
+    private readonly Dictionary<string, (string Owner, DateTime ExpiresAt)> _leases = new();
+
+    public Task<PluginJobState?> GetAsync(string jobId, CancellationToken cancellationToken = default)
+    {
+        _jobs.TryGetValue(jobId, out var job);
+        return Task.FromResult(job);
+    }
+
+    public Task SaveAsync(PluginJobState job, CancellationToken cancellationToken = default)
+    {
+        job.UpdatedAt = DateTime.UtcNow;
+        _jobs[job.JobId] = job;
+        return Task.CompletedTask;
+    }
+
+    public Task DeleteAsync(string jobId, CancellationToken cancellationToken = default)
+    {
+        _jobs.TryRemove(jobId, out _);
+        lock (_leases)
+        {
+            _leases.Remove(jobId);
+        }
+        return Task.CompletedTask;
+    }
+
+    public Task<IReadOnlyList<PluginJobState>> GetRunningAsync(CancellationToken cancellationToken = default)
+    {
+        IReadOnlyList<PluginJobState> running = _jobs.Values
+            .Where(j => j.Status == PluginJobStatus.Running)
+            .ToList();
+        return Task.FromResult(running);
+    }
+
+    public Task<bool> TryClaimAsync(string jobId, string owner, TimeSpan leaseDuration, CancellationToken cancellationToken = default)
+    {
+        var now = DateTime.UtcNow;
+        lock (_leases)
+        {
+            if (_leases.TryGetValue(jobId, out var lease) && lease.Owner != owner && lease.ExpiresAt > now)
+            {
+                return Task.FromResult(false);
+            }
+
+            _leases[jobId] = (owner, now + leaseDuration);
+            return Task.FromResult(true);
+        }
+    }
+
+    public Task ReleaseAsync(string jobId, string owner, CancellationToken cancellationToken = default)
+    {
+        lock (_leases)
+        {
+            if (_leases.TryGetValue(jobId, out var lease) && lease.Owner == owner)
+            {
+                _leases.Remove(jobId);
+            }
+        }
+        return Task.CompletedTask;
+    }
+}
@@ -0,0 +1,109 @@
+using System.Collections.Concurrent;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// In-memory implementation of IPluginMessageBus.
+/// Messages are dropped once they are older than the retention period; journeys without
+/// recent messages are swept at most once per retention period.
+/// </summary>
+public class InMemoryPluginMessageBus : IPluginMessageBus
+{
+    private readonly ConcurrentDictionary<string, List<PluginMessage>> _journeys = new();
+    private readonly TimeSpan _retention;
+    private long _nextSweepTicks;
+
+    public InMemoryPluginMessageBus(TimeSpan? retention = null)
+    {
+        _retention = retention ?? TimeSpan.FromHours(1);
+        _nextSweepTicks = (DateTime.UtcNow + _retention).Ticks;
+    }
+
+    public Task PublishAsync(string journeyId, PluginMessage message, CancellationToken cancellationToken = default)
+    {
+        while (true)
+        {
+            var messages = _journeys.GetOrAdd(journeyId, _ => new List<PluginMessage>());
+            lock (messages)
+            {
+                // A sweep may have removed the journey's list after it was looked up
+                if (!_journeys.TryGetValue(journeyId, out var current) || current != messages)
+                {
+                    continue;
+                }
+
+                RemoveExpired(messages);
+                messages.Add(message);
+                break;
+            }
+        }
+
+        SweepIfDue();
+        return Task.CompletedTask;
+    }
+
+    public Task<IReadOnlyDictionary<string, IReadOnlyList<PluginMessage>>> GetMessagesAsync(
+        string journeyId,
+        IEnumerable<string> topics,
+        CancellationToken cancellationToken = default)
+    {
+        var result = new Dictionary<string, IReadOnlyList<PluginMessage>>();
+        if (!_journeys.TryGetValue(journeyId, out var messages))
+        {
+            return Task.FromResult<IReadOnlyDictionary<string, IReadOnlyList<PluginMessage>>>(result);
+        }
+
+        lock (messages)
+        {
+            RemoveExpired(messages);
+            foreach (var topic in topics.Distinct())
+            {
+                result[topic] = messages.Where(m => m.Topic == topic).ToList();
+            }
+        }
+
+        return Task.FromResult<IReadOnlyDictionary<string, IReadOnlyList<PluginMessage>>>(result);
+    }
+
+    public Task ClearAsync(string journeyId, CancellationToken cancellationToken = default)
+    {
+        _journeys.TryRemove(journeyId, out _);
+        return Task.CompletedTask;
+    }
+
+    /// <summary>
+    /// Drops a journey's messages older than the retention period (messages are kept oldest first)
+    /// </summary>
+    private void RemoveExpired(List<PluginMessage> messages)
+    {
+        var cutoff = DateTime.UtcNow - _retention;
+        var expired = messages.FindIndex(m => m.PublishedAt >= cutoff);
+        messages.RemoveRange(0, expired < 0 ? messages.Count : expired);
+    }
+
+    /// <summary>
+    /// Removes journeys whose newest message has expired, e.g. abandoned journeys that were never cleared
+    /// </summary>
+    private void SweepIfDue()
+    {
+        var now = DateTime.UtcNow;
+        var nextSweep = Interlocked.Read(ref _nextSweepTicks);
+        if (now.Ticks < nextSweep ||
+            Interlocked.CompareExchange(ref _nextSweepTicks, (now + _retention).Ticks, nextSweep) != nextSweep)
+        {
+            return;
+        }
+
+        var cutoff = now - _retention;
+        foreach (var (journeyId, messages) in _journeys)
+        {
+            lock (messages)
+            {
+                if (messages.Count == 0 || messages[^1].PublishedAt < cutoff)
+                {
+                    _journeys.TryRemove(journeyId, out _);
+                }
+            }
+        }
+    }
+}
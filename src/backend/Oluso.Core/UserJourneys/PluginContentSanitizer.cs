@@ -0,0 +1,132 @@
+using System.Net;
+using System.Text;
+using System.Text.RegularExpressions;
+
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Renders the markdown of plugin form content blocks to HTML that is safe to embed in the page.
+/// Only a small subset is supported (paragraphs, headings, lists, bold, italic, code and links);
+/// everything else, including raw HTML, is shown as text.
+/// </summary>
+public static class PluginContentSanitizer
+{
+    /// <summary>
+    /// Maximum length of the markdown of a single content block
+    /// </summary>
+    public const int MaxContentLength = 10_000;
+
+    /// <summary>
+    /// URL schemes allowed in links (otpauth lets authenticator apps be provisioned from a phone)
+    /// </summary>
+    private static readonly HashSet<string> AllowedSchemes = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "https", "http", "mailto", "tel", "otpauth"
+    };
+
+    private static readonly Regex InlinePattern = new(@"`([^`]+)`|\[([^\]]+)\]\(([^)\s]+)\)|\*\*(.+?)\*\*|\*(.+?)\*", RegexOptions.Compiled);
+
+    private static readonly Regex HeadingPattern = new(@"^(#{1,3})\s+(.*)$", RegexOptions.Compiled);
+
+    private static readonly Regex BulletPattern = new(@"^[-*]\s+(.*)$", RegexOptions.Compiled);
+
+    private static readonly Regex NumberedPattern = new(@"^\d+\.\s+(.*)$", RegexOptions.Compiled);
+
+    /// <summary>
+    /// Whether a link target is an absolute URL with an allowed scheme
+    /// </summary>
+    public static bool IsAllowedUrl(string? url)
+    {
+        return Uri.TryCreate(url, UriKind.Absolute, out var uri) && AllowedSchemes.Contains(uri.Scheme);
+    }
+
+    /// <summary>
+    /// Renders markdown to sanitized HTML. Links with disallowed URLs are rendered as their text.
+    /// </summary>
+    public static string RenderMarkdown(string markdown)
+    {
+        var html = new StringBuilder();
+        var blocks = markdown.Replace("\r\n", "\n").Split("\n\n", StringSplitOptions.RemoveEmptyEntries);
+
+        foreach (var block in blocks)
+        {
+            var lines = block.Split('\n', StringSplitOptions.RemoveEmptyEntries | StringSplitOptions.TrimEntries);
+            if (lines.Length == 0)
+            {
+                continue;
+            }
+
+            if (lines.Length == 1 && HeadingPattern.Match(lines[0]) is { Success: true } heading)
+            {
+                // Form titles are headings already, so block headings start at h4
+                var level = heading.Groups[1].Length + 3;
+                html.Append($"<h{level}>{RenderInline(heading.Groups[2].Value)}</h{level}>");
+            }
+            else if (lines.All(l => BulletPattern.IsMatch(l)))
+            {
+                AppendList(html, "ul", lines.Select(l => BulletPattern.Match(l).Groups[1].Value));
+            }
+            else if (lines.All(l => NumberedPattern.IsMatch(l)))
+            {
+                AppendList(html, "ol", lines.Select(l => NumberedPattern.Match(l).Groups[1].Value));
+            }
+            else
+            {
+                html.Append("<p>").AppendJoin("<br />", lines.Select(RenderInline)).Append("</p>");
+            }
+        }
+
+        return html.ToString();
+    }
+
+    private static void AppendList(StringBuilder html, string tag, IEnumerable<string> items)
+    {
+        html.Append($"<{tag}>");
+        foreach (var item in items)
+        {
+            html.Append("<li>").Append(RenderInline(item)).Append("</li>");
+        }
+        html.Append($"</{tag}>");
+    }
+
+    private static string RenderInline(string text)
+    {
+        var html = new StringBuilder();
+        var position = 0;
+
+        foreach (Match match in InlinePattern.Matches(text))
+        {
+            html.Append(WebUtility.HtmlEncode(text[position..match.Index]));
+            position = match.Index + match.Length;
+
+            if (match.Groups[1].Success)
+            {
+                html.Append("<code>").Append(WebUtility.HtmlEncode(match.Groups[1].Value)).Append("</code>");
+            }
+            else if (match.Groups[2].Success)
+            {
+                var label = RenderInline(match.Groups[2].Value);
+                var url = match.Groups[3].Value;
+                if (IsAllowedUrl(url))
+                {
+                    html.Append($"<a href=\"{WebUtility.HtmlEncode(url)}\" target=\"_blank\" rel=\"noopener noreferrer\">{label}</a>");
+                }
+                else
+                {
+                    html.Append(label);
+                }
+            }
+            else if (match.Groups[4].Success)
+            {
+                html.Append("<strong>").Append(RenderInline(match.Groups[4].Value)).Append("</strong>");
+            }
+            else
+            {
+                html.Append("<em>").Append(RenderInline(match.Groups[5].Value)).Append("</em>");
+            }
+        }
+
+        html.Append(WebUtility.HtmlEncode(text[position..]));
+        return html.ToString();
+    }
+}
@@ -0,0 +1,54 @@
+namespace Oluso.Core.UserJourneys;
+
+/// <summary>
+/// Removes sensitive fields from data before it is passed to or persisted for plugins
+/// </summary>
+public static class PluginInputSanitizer
+{
+    /// <summary>
+    /// Fields that should never be passed to plugins for security reasons
+    /// </summary>
+    private static readonly HashSet<string> SensitiveFields = new(StringComparer.OrdinalIgnoreCase)
+    {
+        "password", "pwd", "pass", "passwd", "secret", "credential",
+        "token", "access_token", "refresh_token", "id_token",
+        "code", "mfa_code", "otp", "totp", "hotp", "verification_code",
+        "pin", "cvv", "cvc", "ssn", "social_security",
+        "private_key", "api_key", "apikey", "auth_code"
+    };
+
+    /// <summary>
+    /// Whether a field name is considered sensitive
+    /// </summary>
+    public static bool IsSensitive(string fieldName) => SensitiveFields.Contains(fieldName);
+
+    /// <summary>
+    /// Returns a copy of the input without sensitive fields.
+    /// Fields in <paramref name="allowedFields"/> are kept, e.g. masked inputs the plugin asked for itself.
+    /// </summary>
+    public static IDictionary<string, object>? Sanitize(
+        IDictionary<string, object>? input,
+        IReadOnlyCollection<string>? allowedFields = null)
+    {
+        if (input == null) return null;
+
+        return input
+            .Where(kv => !IsSensitive(kv.Key) || allowedFields?.Contains(kv.Key) == true)
+            .ToDictionary(kv => kv.Key, kv => kv.Value);
+    }
+
+    /// <summary>
+    /// Returns a copy of the input without sensitive fields or any of <paramref name="excludedFields"/>
+    /// </summary>
+    public static IDictionary<string, object>? SanitizeExcluding(
+        IDictionary<string, object>? input,
+        IReadOnlyCollection<string>? excludedFields)
+    {
+        var sanitized = Sanitize(input);
+        if (sanitized == null || excludedFields == null) return sanitized;
+
+        return sanitized
+            .Where(kv => !excludedFields.Contains(kv.Key))
+            .ToDictionary(kv => kv.Key, kv => kv.Value);
+    }
+}
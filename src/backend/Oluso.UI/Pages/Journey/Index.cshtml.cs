@@ -1,3 +1,5 @@
+using System.Text.Json;
+using Microsoft.AspNetCore.Http;
 using Microsoft.AspNetCore.Identity;
 using Microsoft.AspNetCore.Mvc;
 using Microsoft.AspNetCore.Mvc.RazorPages;
@@ -145,6 +147,18 @@ public class IndexModel : PageModel
             .Where(f => !f.Key.StartsWith("__RequestVerification") && !f.Key.StartsWith("__VIEWSTATE"))
             .ToDictionary(f => f.Key, f => (object)f.Value.ToString());
 
+        // Rejected uploads show the current form again with the problem instead of reaching the step
+        var uploadErrors = await StoreUploadedFilesAsync(state, userInput);
+        if (uploadErrors.Count > 0)
+        {
+            ErrorMessage = string.Join(" ", uploadErrors);
+            return await HandleJourneyResultAsync(await _orchestrator.ContinueJourneyAsync(JourneyId, new JourneyStepInput
+            {
+                StepId = state.CurrentStepId,
+                Action = "init"
+            }));
+        }
+
         _logger.LogDebug("Journey {JourneyId} received user input: {InputKeys}",
             JourneyId, string.Join(", ", userInput.Keys));
 
@@ -158,6 +172,137 @@ public class IndexModel : PageModel
         return await HandleJourneyResultAsync(result);
     }
 
+    /// <summary>
+    /// Fetches the options of a select field backed by a plugin options function.
+    /// The posted form is the partial form state; the search text is posted as __query.
+    /// </summary>
+    public async Task<IActionResult> OnPostOptionsAsync(string field)
+    {
+        var state = await _stateStore.GetAsync(JourneyId);
+        if (state == null || (state.ExpiresAt.HasValue && state.ExpiresAt < DateTime.UtcNow))
+        {
+            return NotFound();
+        }
+
+        // Only functions declared by the form currently shown can be called
+        var source = PluginFieldOptionsSource.Find(state, field);
+        var executor = _serviceProvider.GetService<IPluginExecutor>();
+        if (source == null || executor == null)
+        {
+            return NotFound();
+        }
+
+        // Masked fields are dropped even if a client sends them; options functions never see secrets
+        var sensitiveFields = DynamicFormSchema.GetSensitiveFields(state.Data);
+        var formState = Request.Form
+            .Where(f => !f.Key.StartsWith("__RequestVerification") && !sensitiveFields.Contains(f.Key))
+            .ToDictionary(f => f.Key, f => (object)f.Value.ToString());
+
+        var stepConfig = await GetPluginStepConfigAsync(state.PolicyId, source.StepId);
+        var configResolver = _serviceProvider.GetService<IPluginConfigResolver>();
+        var resolvedConfig = configResolver != null
+            ? await configResolver.ResolveAsync(source.PluginName, state.TenantId, stepConfig, HttpContext.RequestAborted)
+            : null;
+
+        var options = await executor.GetFieldOptionsAsync(source.PluginName, source.FunctionName, new PluginExecutionContext
+        {
+            UserId = state.UserId,
+            TenantId = state.TenantId,
+            JourneyId = JourneyId,
+            StepId = source.StepId,
+            Input = formState,
+            JourneyData = state.Data,
+            Config = resolvedConfig?.Values ?? stepConfig,
+            ConfigProvenance = resolvedConfig?.Provenance,
+            SensitiveInputFields = DynamicFormSchema.GetSensitiveFields(state.Data, source.StepId)
+        }, HttpContext.RequestAborted);
+
+        return new JsonResult(options.Select(o => new { value = o.Value, label = o.Label }));
+    }
+
+    /// <summary>
+    /// Reads the plugin <c>config</c> setting of a journey step
+    /// </summary>
+    private async Task<Dictionary<string, object>?> GetPluginStepConfigAsync(string policyId, string stepId)
+    {
+        var policy = await _policyStore.GetByIdAsync(policyId);
+        var step = policy?.Steps.FirstOrDefault(s => s.Id == stepId);
+        if (step?.Configuration == null || !step.Configuration.TryGetValue("config", out var config))
+        {
+            return null;
+        }
+
+        return config switch
+        {
+            Dictionary<string, object> values => values,
+            JsonElement { ValueKind: JsonValueKind.Object } element => element.Deserialize<Dictionary<string, object>>(),
+            _ => null
+        };
+    }
+
+    /// <summary>
+    /// Stores uploaded files host-side and passes steps an attachment handle in place of the file.
+    /// Only the current form's file fields take uploads, within their accepted types and size; if any
+    /// file is rejected nothing is stored and the validation errors are returned.
+    /// </summary>
+    private async Task<IReadOnlyList<string>> StoreUploadedFilesAsync(JourneyState state, Dictionary<string, object> userInput)
+    {
+        if (!Request.HasFormContentType || Request.Form.Files.Count == 0)
+        {
+            return Array.Empty<string>();
+        }
+
+        var files = Request.Form.Files.Where(f => f.Length > 0).ToList();
+        if (files.Count == 0)
+        {
+            return Array.Empty<string>();
+        }
+
+        var attachmentStore = _serviceProvider.GetService<IPluginAttachmentStore>();
+        if (attachmentStore == null)
+        {
+            _logger.LogWarning("Attachment store not available, rejecting uploaded files for journey {JourneyId}", JourneyId);
+            return new[] { "File uploads are not available." };
+        }
+
+        var fileFields = PluginFileField.FromJourneyData(state.Data);
+        var errors = new List<string>();
+        foreach (var file in files)
+        {
+            if (!fileFields.TryGetValue(file.Name, out var field))
+            {
+                _logger.LogWarning("Rejecting upload to {FieldName} for journey {JourneyId}: not a file field of the current form",
+                    file.Name, JourneyId);
+                errors.Add($"{file.FileName} was not expected.");
+                continue;
+            }
+
+            var error = field.Validate(file.FileName, GetContentType(file), file.Length, attachmentStore.MaxSizeBytes);
+            if (error != null)
+            {
+                _logger.LogInformation("Rejecting upload {FieldName} of {Size} bytes ({ContentType}) for journey {JourneyId}: {Error}",
+                    file.Name, file.Length, GetContentType(file), JourneyId, error);
+                errors.Add($"{error}.");
+            }
+        }
+
+        if (errors.Count > 0)
+        {
+            return errors;
+        }
+
+        foreach (var file in files)
+        {
+            await using var content = file.OpenReadStream();
+            userInput[file.Name] = await attachmentStore.StoreAsync(JourneyId, file.FileName, GetContentType(file), content);
+        }
+
+        return Array.Empty<string>();
+    }
+
+    private static string GetContentType(IFormFile file) =>
+        string.IsNullOrEmpty(file.ContentType) ? "application/octet-stream" : file.ContentType;
+
     /// <summary>
     /// Handle callback from external identity provider
     /// </summary>
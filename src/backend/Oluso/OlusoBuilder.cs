@@ -51,6 +51,11 @@ public class OlusoBuilder
 
         // Plugin registry - collects all managed plugins
         Services.TryAddSingleton<IManagedPluginRegistry, DefaultManagedPluginRegistry>();
+        Services.TryAddSingleton<IPluginJobStore, InMemoryPluginJobStore>();
+        Services.TryAddSingleton<IPluginMessageBus, InMemoryPluginMessageBus>();
+        Services.TryAddSingleton<IPluginIdempotencyStore, InMemoryPluginIdempotencyStore>();
+        Services.TryAddSingleton<IPluginAttachmentStore, InMemoryPluginAttachmentStore>();
+        Services.TryAddScoped<IPluginConfigResolver, DefaultPluginConfigResolver>();
 
         // Tenant settings provider (can be overridden by user)
         Services.TryAddScoped<ITenantSettingsProvider, DefaultTenantSettingsProvider>();
@@ -186,7 +191,11 @@ public class OlusoBuilder
                 sp.GetRequiredService<UserJourneys.Plugins.IPluginWatcher>(),
                 sp.GetRequiredService<ILogger<UserJourneys.Plugins.ExtismPluginExecutor>>(),
                 sp.GetService<IPluginStore>(),
-                sp.GetService<PluginExecutorOptions>()
+                sp.GetService<PluginExecutorOptions>(),
+                sp.GetService<IPluginJobStore>(),
+                sp.GetService<IPluginMessageBus>(),
+                sp.GetService<IPluginIdempotencyStore>(),
+                sp.GetService<IPluginAttachmentStore>()
             );
 
             // Start watching for plugin changes if hot-reload is enabled
@@ -199,6 +208,9 @@ public class OlusoBuilder
             return executor;
         });
 
+        // Run long-running plugin jobs queued by journey steps, resuming interrupted ones
+        Services.AddHostedService<UserJourneys.Plugins.PluginJobProcessor>();
+
         return this;
     }
 
@@ -0,0 +1,236 @@
+using System.Text.Json;
+using Microsoft.Extensions.Caching.Distributed;
+using Microsoft.Extensions.Logging;
+using Oluso.Core.UserJourneys;
+
+namespace Oluso.UserJourneys;
+
+/// <summary>
+/// Plugin job store using IDistributedCache (Redis, SQL Server, etc.), so running jobs
+/// survive restarts and are resumed by the PluginJobProcessor.
+/// Configure the underlying cache with AddStackExchangeRedisCache, AddSqlServerCache, etc.
+/// IDistributedCache has no compare-and-set, so claims and running index updates are written and
+/// then read back after <see cref="DistributedCachePluginJobStoreOptions.WriteSettleDelay"/>: of two
+/// instances writing at the same time, only the one whose write landed last goes ahead.
+/// </summary>
+public class DistributedCachePluginJobStore : IPluginJobStore
+{
+    private readonly IDistributedCache _cache;
+    private readonly ILogger<DistributedCachePluginJobStore> _logger;
+    private readonly DistributedCachePluginJobStoreOptions _options;
+
+    /// <summary>
+    /// Serializes read-modify-write updates of the running job index within this process
+    /// </summary>
+    private readonly SemaphoreSlim _indexLock = new(1, 1);
+
+    private static readonly JsonSerializerOptions JsonOptions = new()
+    {
+        PropertyNamingPolicy = JsonNamingPolicy.CamelCase,
+        WriteIndented = false
+    };
+
+    public DistributedCachePluginJobStore(
+        IDistributedCache cache,
+        ILogger<DistributedCachePluginJobStore> logger,
+        DistributedCachePluginJobStoreOptions? options = null)
+    {
+        _cache = cache;
+        _logger = logger;
+        _options = options ?? new DistributedCachePluginJobStoreOptions();
+    }
+
+    public async Task<PluginJobState?> GetAsync(string jobId, CancellationToken cancellationToken = default)
+    {
+        var data = await _cache.GetStringAsync(GetKey(jobId), cancellationToken);
+
+        if (string.IsNullOrEmpty(data))
+        {
+            return null;
+        }
+
+        try
+        {
+            return JsonSerializer.Deserialize<PluginJobState>(data, JsonOptions);
+        }
+        catch (JsonException ex)
+        {
+            _logger.LogWarning(ex, "Failed to deserialize plugin job state for {JobId}", jobId);
+            return null;
+        }
+    }
+
+    public async Task SaveAsync(PluginJobState job, CancellationToken cancellationToken = default)
+    {
+        job.UpdatedAt = DateTime.UtcNow;
+
+        var options = new DistributedCacheEntryOptions
+        {
+            AbsoluteExpirationRelativeToNow = _options.JobExpiration
+        };
+
+        await _cache.SetStringAsync(GetKey(job.JobId), JsonSerializer.Serialize(job, JsonOptions), options, cancellationToken);
+
+        await UpdateRunningIndexAsync(job.JobId, job.Status == PluginJobStatus.Running, cancellationToken);
+    }
+
+    public async Task DeleteAsync(string jobId, CancellationToken cancellationToken = default)
+    {
+        await UpdateRunningIndexAsync(jobId, running: false, cancellationToken);
+        await _cache.RemoveAsync(GetKey(jobId), cancellationToken);
+    }
+
+    public async Task<IReadOnlyList<PluginJobState>> GetRunningAsync(CancellationToken cancellationToken = default)
+    {
+        var jobs = new List<PluginJobState>();
+        foreach (var jobId in await GetRunningIndexAsync(cancellationToken))
+        {
+            var job = await GetAsync(jobId, cancellationToken);
+            if (job?.Status == PluginJobStatus.Running)
+            {
+                jobs.Add(job);
+            }
+        }
+
+        return jobs;
+    }
+
+    public async Task<bool> TryClaimAsync(
+        string jobId,
+        string owner,
+        TimeSpan leaseDuration,
+        CancellationToken cancellationToken = default)
+    {
+        var key = GetLeaseKey(jobId);
+        var holder = await _cache.GetStringAsync(key, cancellationToken);
+        if (holder != null && holder != owner)
+        {
+            return false;
+        }
+
+        await _cache.SetStringAsync(key, owner, new DistributedCacheEntryOptions
+        {
+            AbsoluteExpirationRelativeToNow = leaseDuration
+        }, cancellationToken);
+
+        // Another instance may have claimed the job between the read and the write
+        await Task.Delay(_options.WriteSettleDelay, cancellationToken);
+        return await _cache.GetStringAsync(key, cancellationToken) == owner;
+    }
+
+    public async Task ReleaseAsync(string jobId, string owner, CancellationToken cancellationToken = default)
+    {
+        var key = GetLeaseKey(jobId);
+        if (await _cache.GetStringAsync(key, cancellationToken) == owner)
+        {
+            await _cache.RemoveAsync(key, cancellationToken);
+        }
+    }
+
+    private string GetKey(string jobId) =>
+        $"{_options.KeyPrefix}plugin-job:{jobId}";
+
+    private string GetLeaseKey(string jobId) =>
+        $"{_options.KeyPrefix}plugin-job-lease:{jobId}";
+
+    private string GetRunningIndexKey() =>
+        $"{_options.KeyPrefix}plugin-jobs:running";
+
+    private async Task<HashSet<string>> GetRunningIndexAsync(CancellationToken cancellationToken)
+    {
+        var indexData = await _cache.GetStringAsync(GetRunningIndexKey(), cancellationToken);
+        if (string.IsNullOrEmpty(indexData))
+        {
+            return new HashSet<string>();
+        }
+
+        try
+        {
+            return JsonSerializer.Deserialize<HashSet<string>>(indexData, JsonOptions) ?? new HashSet<string>();
+        }
+        catch (JsonException ex)
+        {
+            _logger.LogWarning(ex, "Failed to deserialize the running plugin job index");
+            return new HashSet<string>();
+        }
+    }
+
+    /// <summary>
+    /// Adds a job to or removes it from the running index. The lock serializes updates within this process;
+    /// an update overwritten by another instance is detected by reading the index back and made again.
+    /// </summary>
+    private async Task UpdateRunningIndexAsync(string jobId, bool running, CancellationToken cancellationToken)
+    {
+        await _indexLock.WaitAsync(cancellationToken);
+        try
+        {
+            for (var attempt = 0; attempt < _options.MaxIndexUpdateAttempts; attempt++)
+            {
+                var jobIds = await GetRunningIndexAsync(cancellationToken);
+                var changed = running ? jobIds.Add(jobId) : jobIds.Remove(jobId);
+                if (!changed)
+                {
+                    return;
+                }
+
+                if (jobIds.Count == 0)
+                {
+                    await _cache.RemoveAsync(GetRunningIndexKey(), cancellationToken);
+                }
+                else
+                {
+                    var options = new DistributedCacheEntryOptions
+                    {
+                        AbsoluteExpirationRelativeToNow = _options.JobExpiration
+                    };
+
+                    await _cache.SetStringAsync(
+                        GetRunningIndexKey(),
+                        JsonSerializer.Serialize(jobIds, JsonOptions),
+                        options,
+                        cancellationToken);
+                }
+
+                await Task.Delay(_options.WriteSettleDelay, cancellationToken);
+                if ((await GetRunningIndexAsync(cancellationToken)).Contains(jobId) == running)
+                {
+                    return;
+                }
+            }
+
+            _logger.LogWarning("Could not update the running plugin job index for {JobId} after {Attempts} attempts",
+                jobId, _options.MaxIndexUpdateAttempts);
+        }
+        finally
+        {
+            _indexLock.Release();
+        }
+    }
+}
+
+/// <summary>
+/// Options for the distributed cache plugin job store
+/// </summary>
+public class DistributedCachePluginJobStoreOptions
+{
+    /// <summary>
+    /// Key prefix for all cache entries (default: "oluso:")
+    /// </summary>
+    public string KeyPrefix { get; set; } = "oluso:";
+
+    /// <summary>
+    /// How long a job is kept after its last update (default: 7 days)
+    /// </summary>
+    public TimeSpan JobExpiration { get; set; } = TimeSpan.FromDays(7);
+
+    /// <summary>
+    /// How long to wait before reading back a claim or running index update to check that no other
+    /// instance overwrote it; longer than the cache's write latency (default: 100 ms)
+    /// </summary>
+    public TimeSpan WriteSettleDelay { get; set; } = TimeSpan.FromMilliseconds(100);
+
+    /// <summary>
+    /// How often a running index update overwritten by another instance is made again (default: 5)
+    /// </summary>
+    public int MaxIndexUpdateAttempts { get; set; } = 5;
+}
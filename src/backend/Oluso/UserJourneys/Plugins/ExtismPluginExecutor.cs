@@ -22,37 +22,64 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
     private readonly IManagedPluginRegistry _managedPluginRegistry;
     private readonly IPluginWatcher _pluginWatcher;
     private readonly IPluginStore? _pluginStore;
+    private readonly IPluginJobStore _jobStore;
+    private readonly IPluginMessageBus _messageBus;
+    private readonly IPluginIdempotencyStore _idempotencyStore;
+    private readonly bool _ownsIdempotencyStore;
+    private readonly IPluginAttachmentStore _attachmentStore;
     private readonly ILogger<ExtismPluginExecutor> _logger;
     private readonly PluginExecutorOptions _options;
     private bool _disposed;
 
     /// <summary>
-    /// Fields that should never be passed to plugins for security reasons
+    /// Invocations with an idempotency key that are still running, so concurrent retries await the first one
     /// </summary>
-    private static readonly HashSet<string> SensitiveFields = new(StringComparer.OrdinalIgnoreCase)
-    {
-        "password", "pwd", "pass", "passwd", "secret", "credential",
-        "token", "access_token", "refresh_token", "id_token",
-        "code", "mfa_code", "otp", "totp", "hotp", "verification_code",
-        "pin", "cvv", "cvc", "ssn", "social_security",
-        "private_key", "api_key", "apikey", "auth_code"
-    };
+    private readonly ConcurrentDictionary<string, Lazy<Task<PluginExecutionResult>>> _pendingInvocations = new();
+
+    /// <summary>
+    /// Job whose invocation is currently running on this async flow, used by host functions
+    /// </summary>
+    private static readonly AsyncLocal<PluginJobState?> CurrentJob = new();
+
+    /// <summary>
+    /// WASM invocation currently running on this async flow, used by host functions
+    /// </summary>
+    private static readonly AsyncLocal<InvocationScope?> CurrentInvocation = new();
+
+    /// <summary>
+    /// Optional export invoked to undo a side-effecting function when the journey fails
+    /// </summary>
+    private const string CompensateExport = "compensate";
 
     private static readonly JsonSerializerOptions JsonOptions = new()
     {
         PropertyNamingPolicy = JsonNamingPolicy.CamelCase
     };
 
+    private static readonly JsonSerializerOptions FieldOptionsJsonOptions = new()
+    {
+        PropertyNameCaseInsensitive = true
+    };
+
     public ExtismPluginExecutor(
         IManagedPluginRegistry managedPluginRegistry,
         IPluginWatcher pluginWatcher,
         ILogger<ExtismPluginExecutor> logger,
         IPluginStore? pluginStore = null,
-        PluginExecutorOptions? options = null)
+        PluginExecutorOptions? options = null,
+        IPluginJobStore? jobStore = null,
+        IPluginMessageBus? messageBus = null,
+        IPluginIdempotencyStore? idempotencyStore = null,
+        IPluginAttachmentStore? attachmentStore = null)
     {
         _managedPluginRegistry = managedPluginRegistry;
         _pluginWatcher = pluginWatcher;
         _pluginStore = pluginStore;
+        _jobStore = jobStore ?? new InMemoryPluginJobStore();
+        _messageBus = messageBus ?? new InMemoryPluginMessageBus();
+        _idempotencyStore = idempotencyStore ?? new InMemoryPluginIdempotencyStore();
+        _ownsIdempotencyStore = idempotencyStore == null;
+        _attachmentStore = attachmentStore ?? new InMemoryPluginAttachmentStore();
         _logger = logger;
         _options = options ?? new PluginExecutorOptions();
 
@@ -65,29 +92,192 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
         string functionName,
         PluginExecutionContext context,
         CancellationToken cancellationToken = default)
+    {
+        if (string.IsNullOrEmpty(context.IdempotencyKey))
+        {
+            return await ExecuteUncachedAsync(pluginName, functionName, context, cancellationToken);
+        }
+
+        var cacheKey = $"{pluginName}:{functionName}:{context.IdempotencyKey}";
+
+        // A retry arriving while the first call is still running gets its result instead of running again
+        var invocation = _pendingInvocations.GetOrAdd(cacheKey, _ => new Lazy<Task<PluginExecutionResult>>(
+            () => ExecuteOnceAsync(pluginName, functionName, cacheKey, context, cancellationToken)));
+        return await invocation.Value.WaitAsync(cancellationToken);
+    }
+
+    private async Task<PluginExecutionResult> ExecuteOnceAsync(
+        string pluginName,
+        string functionName,
+        string cacheKey,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken)
+    {
+        try
+        {
+            var cached = await _idempotencyStore.GetAsync(cacheKey, cancellationToken);
+            if (cached != null)
+            {
+                _logger.LogInformation("Suppressed duplicate invocation of {PluginName}.{FunctionName} (idempotency key {IdempotencyKey})",
+                    pluginName, functionName, context.IdempotencyKey);
+                return cached;
+            }
+
+            var result = await ExecuteUncachedAsync(pluginName, functionName, context, cancellationToken);
+
+            // Only completed results are cached; failures and input requests must be able to run again
+            if (result.IsCompleted)
+            {
+                await _idempotencyStore.SetAsync(cacheKey, result, _options.IdempotencyWindow, cancellationToken);
+            }
+
+            return result;
+        }
+        finally
+        {
+            // Later calls read the idempotency store (or run again after a failure)
+            _pendingInvocations.TryRemove(cacheKey, out _);
+        }
+    }
+
+    private async Task<PluginExecutionResult> ExecuteUncachedAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken)
     {
         // First check for managed (.NET) plugins
         var managedPlugin = _managedPluginRegistry.Get(pluginName);
         if (managedPlugin != null)
         {
             _logger.LogDebug("Executing managed plugin: {PluginName}.{FunctionName}", pluginName, functionName);
-            return await managedPlugin.ExecuteAsync(functionName, context, cancellationToken);
+            var result = await managedPlugin.ExecuteAsync(functionName, context, cancellationToken);
+
+            if (context.JourneyId != null)
+            {
+                await _messageBus.PublishAllAsync(context.JourneyId, managedPlugin.Name, result.PublishedMessages, cancellationToken);
+            }
+
+            return result;
+        }
+
+        var loadedPlugin = await GetOrLoadPluginAsync(pluginName, context.TenantId, cancellationToken);
+        if (loadedPlugin != null)
+        {
+            return await ExecuteWasmPluginAsync(loadedPlugin, functionName, context, cancellationToken);
+        }
+
+        return new PluginExecutionResult
+        {
+            Success = false,
+            Error = $"Plugin '{pluginName}' not found",
+            Action = PluginAction.Fail
+        };
+    }
+
+    public bool SupportsCompensation(string pluginName)
+    {
+        var managedPlugin = _managedPluginRegistry.Get(pluginName);
+        if (managedPlugin != null)
+        {
+            return managedPlugin.SupportsCompensation;
+        }
+
+        return _plugins.TryGetValue(pluginName, out var loadedPlugin) &&
+               loadedPlugin.Plugin?.FunctionExists(CompensateExport) == true;
+    }
+
+    public async Task<PluginExecutionResult> CompensateAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        IDictionary<string, object>? originalOutput,
+        CancellationToken cancellationToken = default)
+    {
+        var managedPlugin = _managedPluginRegistry.Get(pluginName);
+        if (managedPlugin != null)
+        {
+            _logger.LogDebug("Compensating managed plugin: {PluginName}.{FunctionName}", pluginName, functionName);
+            return await managedPlugin.CompensateAsync(functionName, context, originalOutput, cancellationToken);
+        }
+
+        var loadedPlugin = await GetOrLoadPluginAsync(pluginName, context.TenantId, cancellationToken);
+        if (loadedPlugin == null)
+        {
+            return new PluginExecutionResult
+            {
+                Success = false,
+                Error = $"Plugin '{pluginName}' not found",
+                Action = PluginAction.Fail
+            };
+        }
+
+        if (loadedPlugin.Plugin?.FunctionExists(CompensateExport) != true)
+        {
+            _logger.LogDebug("Plugin {PluginName} does not export {Export}; nothing to compensate",
+                pluginName, CompensateExport);
+            return new PluginExecutionResult { Success = true };
         }
 
+        _logger.LogDebug("Compensating WASM plugin: {PluginName}.{FunctionName}", pluginName, functionName);
+        return await ExecuteWasmPluginAsync(loadedPlugin, functionName, context, cancellationToken,
+            CompensateExport, originalOutput ?? new Dictionary<string, object>());
+    }
+
+    public async Task<IReadOnlyList<DynamicFormOption>> GetFieldOptionsAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken = default)
+    {
+        var result = await ExecuteAsync(pluginName, functionName, context, cancellationToken);
+        if (!result.Success)
+        {
+            _logger.LogWarning("Options function {PluginName}.{FunctionName} failed: {Error}",
+                pluginName, functionName, result.Error);
+            return Array.Empty<DynamicFormOption>();
+        }
+
+        if (result.Output?.TryGetValue("options", out var options) != true || options == null)
+        {
+            return Array.Empty<DynamicFormOption>();
+        }
+
+        try
+        {
+            return JsonSerializer.SerializeToElement(options, JsonOptions)
+                .Deserialize<List<DynamicFormOption>>(FieldOptionsJsonOptions) ?? new List<DynamicFormOption>();
+        }
+        catch (JsonException ex)
+        {
+            _logger.LogWarning(ex, "Options function {PluginName}.{FunctionName} returned invalid options",
+                pluginName, functionName);
+            return Array.Empty<DynamicFormOption>();
+        }
+    }
+
+    /// <summary>
+    /// Gets a loaded WASM plugin, loading it from the plugin store or directory if needed
+    /// </summary>
+    private async Task<LoadedPlugin?> GetOrLoadPluginAsync(
+        string pluginName,
+        string? tenantId,
+        CancellationToken cancellationToken)
+    {
         // Check for loaded WASM plugins
         if (_plugins.TryGetValue(pluginName, out var loadedPlugin))
         {
-            return await ExecuteWasmPluginAsync(loadedPlugin, functionName, context, cancellationToken);
+            return loadedPlugin;
         }
 
         // Try to load from plugin store
         if (_pluginStore != null)
         {
-            var wasmBytes = await _pluginStore.GetPluginBytesAsync(pluginName, context.TenantId, cancellationToken);
+            var wasmBytes = await _pluginStore.GetPluginBytesAsync(pluginName, tenantId, cancellationToken);
             if (wasmBytes != null)
             {
                 await LoadPluginAsync(pluginName, wasmBytes, cancellationToken);
-                return await ExecuteAsync(pluginName, functionName, context, cancellationToken);
+                return _plugins.GetValueOrDefault(pluginName);
             }
         }
 
@@ -99,18 +289,167 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
             {
                 var wasmBytes = await File.ReadAllBytesAsync(pluginPath, cancellationToken);
                 await LoadPluginAsync(pluginName, wasmBytes, cancellationToken);
-                return await ExecuteAsync(pluginName, functionName, context, cancellationToken);
+                return _plugins.GetValueOrDefault(pluginName);
+            }
+        }
+
+        return null;
+    }
+
+    public async Task<PluginExecutionResult> ExecuteAllPagesAsync(
+        string pluginName,
+        string functionName,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken = default)
+    {
+        var items = new List<object>();
+        var pageToken = context.PageToken;
+
+        // Every token requested so far; any repeat means the plugin's cursor is cycling
+        var seenTokens = new HashSet<string>(StringComparer.Ordinal);
+        if (!string.IsNullOrEmpty(pageToken))
+        {
+            seenTokens.Add(pageToken);
+        }
+
+        for (var page = 0; page < _options.MaxPages; page++)
+        {
+            var pageContext = context.ForInvocation($"page{page}", pageToken: pageToken);
+
+            var result = await ExecuteAsync(pluginName, functionName, pageContext, cancellationToken);
+            if (!result.Success)
+            {
+                return result;
+            }
+
+            if (result.Output != null && result.Output.TryGetValue("items", out var pageItems))
+            {
+                items.AddRange(EnumerateItems(pageItems));
+            }
+
+            if (string.IsNullOrEmpty(result.NextPageToken))
+            {
+                var output = result.Output != null
+                    ? new Dictionary<string, object>(result.Output)
+                    : new Dictionary<string, object>();
+                output["items"] = items;
+
+                return new PluginExecutionResult
+                {
+                    Success = true,
+                    Output = output,
+                    Action = result.Action
+                };
+            }
+
+            if (!seenTokens.Add(result.NextPageToken))
+            {
+                _logger.LogError("Plugin {PluginName}.{FunctionName} returned a page token it already returned: {PageToken}",
+                    pluginName, functionName, result.NextPageToken);
+                return new PluginExecutionResult
+                {
+                    Success = false,
+                    Error = "Plugin returned a repeated page token",
+                    Action = PluginAction.Fail
+                };
             }
+
+            pageToken = result.NextPageToken;
         }
 
+        _logger.LogError("Plugin {PluginName}.{FunctionName} exceeded the maximum of {MaxPages} pages",
+            pluginName, functionName, _options.MaxPages);
         return new PluginExecutionResult
         {
             Success = false,
-            Error = $"Plugin '{pluginName}' not found",
+            Error = $"Plugin returned more than {_options.MaxPages} pages",
             Action = PluginAction.Fail
         };
     }
 
+    public async Task<PluginJobState> RunJobAsync(
+        string pluginName,
+        string functionName,
+        string jobId,
+        PluginExecutionContext context,
+        CancellationToken cancellationToken = default)
+    {
+        var job = await _jobStore.GetAsync(jobId, cancellationToken);
+        if (job == null || job.Status != PluginJobStatus.Running)
+        {
+            job = new PluginJobState
+            {
+                JobId = jobId,
+                PluginName = pluginName,
+                FunctionName = functionName,
+                TenantId = context.TenantId,
+                UserId = context.UserId,
+                JourneyId = context.JourneyId,
+                StepId = context.StepId,
+                Input = PluginInputSanitizer.SanitizeExcluding(context.Input, context.SensitiveInputFields),
+                Config = context.Config,
+                Checkpoint = context.Checkpoint
+            };
+            await _jobStore.SaveAsync(job, cancellationToken);
+        }
+        else
+        {
+            _logger.LogInformation("Resuming plugin job {JobId} at {Percent}% after {Invocations} invocations",
+                jobId, job.ProgressPercent, job.Invocations);
+        }
+
+        while (job.Invocations < _options.MaxJobInvocations)
+        {
+            var invocationContext = context.ForInvocation($"batch{job.Invocations}", checkpoint: job.Checkpoint);
+
+            PluginExecutionResult result;
+            CurrentJob.Value = job;
+            try
+            {
+                result = await ExecuteAsync(pluginName, functionName, invocationContext, cancellationToken);
+            }
+            finally
+            {
+                CurrentJob.Value = null;
+            }
+
+            // An invocation cut short by cancellation did not fail; the job resumes from its last checkpoint
+            cancellationToken.ThrowIfCancellationRequested();
+
+            job.Invocations++;
+
+            if (!result.Success)
+            {
+                job.Status = PluginJobStatus.Failed;
+                job.Error = result.Error;
+                await _jobStore.SaveAsync(job, cancellationToken);
+                _logger.LogWarning("Plugin job {JobId} failed: {Error}", jobId, result.Error);
+                return job;
+            }
+
+            if (string.IsNullOrEmpty(result.Checkpoint))
+            {
+                job.Status = PluginJobStatus.Completed;
+                job.ProgressPercent = 100;
+                job.Checkpoint = null;
+                await _jobStore.SaveAsync(job, cancellationToken);
+                _logger.LogInformation("Plugin job {JobId} completed after {Invocations} invocations",
+                    jobId, job.Invocations);
+                return job;
+            }
+
+            job.Checkpoint = result.Checkpoint;
+            await _jobStore.SaveAsync(job, cancellationToken);
+        }
+
+        job.Status = PluginJobStatus.Failed;
+        job.Error = $"Job exceeded the maximum of {_options.MaxJobInvocations} invocations";
+        await _jobStore.SaveAsync(job, cancellationToken);
+        _logger.LogError("Plugin job {JobId} exceeded the maximum of {MaxInvocations} invocations",
+            jobId, _options.MaxJobInvocations);
+        return job;
+    }
+
     public Task LoadPluginAsync(string pluginName, byte[] wasmBytes, CancellationToken cancellationToken = default)
     {
         try
@@ -137,7 +476,7 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
                 AllowedHosts = new[] { "*" } // Allow network access if needed
             };
 
-            var plugin = new Plugin(manifest, new HostFunction[] { }, withWasi: true);
+            var plugin = new Plugin(manifest, CreateHostFunctions(), withWasi: true);
 
             var loadedPlugin = new LoadedPlugin
             {
@@ -269,7 +608,7 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
                 AllowedHosts = new[] { "*" }
             };
 
-            var plugin = new Plugin(manifest, new HostFunction[] { }, withWasi: true);
+            var plugin = new Plugin(manifest, CreateHostFunctions(), withWasi: true);
 
             var loadedPlugin = new LoadedPlugin
             {
@@ -297,8 +636,12 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
         LoadedPlugin loadedPlugin,
         string functionName,
         PluginExecutionContext context,
-        CancellationToken cancellationToken)
+        CancellationToken cancellationToken,
+        string? exportName = null,
+        IDictionary<string, object>? originalOutput = null)
     {
+        exportName ??= functionName;
+
         if (loadedPlugin.Plugin == null)
         {
             return new PluginExecutionResult
@@ -312,31 +655,62 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
         try
         {
             // Prepare input - sanitize to prevent leaking sensitive data
-            var sanitizedInput = SanitizeInput(context.Input);
+            var sanitizedInput = PluginInputSanitizer.Sanitize(context.Input, context.SensitiveInputFields);
 
             var input = new PluginInput
             {
                 Function = functionName,
                 UserId = context.UserId,
                 TenantId = context.TenantId,
+                StepId = context.StepId,
+                IdempotencyKey = context.IdempotencyKey,
                 Input = sanitizedInput ?? new Dictionary<string, object>(),
-                JourneyData = context.JourneyData ?? new Dictionary<string, object>()
+                JourneyData = context.JourneyData ?? new Dictionary<string, object>(),
+                Config = context.Config ?? new Dictionary<string, object>(),
+                ConfigProvenance = context.ConfigProvenance ?? new Dictionary<string, string>(),
+                PageToken = context.PageToken,
+                Checkpoint = context.Checkpoint != null
+                    ? JsonSerializer.Deserialize<JsonElement>(context.Checkpoint)
+                    : null
             };
 
+            if (context.Messages != null)
+            {
+                input.Messages = context.Messages;
+            }
+            else if (context.JourneyId != null && context.Subscriptions is { Count: > 0 })
+            {
+                input.Messages = await _messageBus.GetMessagesAsync(
+                    context.JourneyId, context.Subscriptions, cancellationToken);
+            }
+
+            if (originalOutput != null)
+            {
+                input.OriginalInput = input.Input;
+                input.OriginalOutput = originalOutput;
+            }
+
             var inputJson = JsonSerializer.Serialize(input, JsonOptions);
 
-            _logger.LogDebug("Executing WASM plugin: {PluginName}.{FunctionName}", loadedPlugin.Name, functionName);
+            _logger.LogDebug("Executing WASM plugin: {PluginName}.{ExportName}", loadedPlugin.Name, exportName);
 
             // Execute with timeout
             using var cts = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
             cts.CancelAfter(_options.ExecutionTimeout);
 
             // Execute the plugin
+            var invocation = new InvocationScope(loadedPlugin.Name, context.JourneyId)
+            {
+                Attachments = await LoadAttachmentsAsync(context.JourneyId, cancellationToken)
+            };
             var outputJson = await Task.Run(() =>
             {
-                return loadedPlugin.Plugin.Call(functionName, inputJson);
+                CurrentInvocation.Value = invocation;
+                return loadedPlugin.Plugin.Call(exportName, inputJson);
             }, cts.Token);
 
+            await PublishMessagesAsync(invocation, cancellationToken);
+
             // Parse output
             var output = JsonSerializer.Deserialize<PluginOutput>(outputJson, JsonOptions);
 
@@ -355,6 +729,10 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
                 Success = output.Success,
                 Error = output.Error,
                 Output = output.Data,
+                NextPageToken = output.NextPageToken,
+                Checkpoint = output.Checkpoint is { ValueKind: not JsonValueKind.Null } checkpoint
+                    ? checkpoint.GetRawText()
+                    : null,
                 Action = output.Action switch
                 {
                     "continue" => PluginAction.Continue,
@@ -366,10 +744,15 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
                 }
             };
         }
+        catch (OperationCanceledException) when (cancellationToken.IsCancellationRequested)
+        {
+            // The caller gave up (e.g. host shutdown); that is not a failure of the plugin
+            throw;
+        }
         catch (OperationCanceledException)
         {
             _logger.LogError("WASM plugin execution timed out: {PluginName}.{FunctionName}",
-                loadedPlugin.Name, functionName);
+                loadedPlugin.Name, exportName);
             return new PluginExecutionResult
             {
                 Success = false,
@@ -380,7 +763,7 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
         catch (Exception ex)
         {
             _logger.LogError(ex, "Error executing WASM plugin: {PluginName}.{FunctionName}",
-                loadedPlugin.Name, functionName);
+                loadedPlugin.Name, exportName);
             return new PluginExecutionResult
             {
                 Success = false,
@@ -398,8 +781,17 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
         public string Function { get; set; } = null!;
         public string? UserId { get; set; }
         public string? TenantId { get; set; }
+        public string? StepId { get; set; }
+        public string? IdempotencyKey { get; set; }
         public IDictionary<string, object> Input { get; set; } = new Dictionary<string, object>();
         public IDictionary<string, object> JourneyData { get; set; } = new Dictionary<string, object>();
+        public IDictionary<string, object> Config { get; set; } = new Dictionary<string, object>();
+        public IDictionary<string, string> ConfigProvenance { get; set; } = new Dictionary<string, string>();
+        public string? PageToken { get; set; }
+        public JsonElement? Checkpoint { get; set; }
+        public IReadOnlyDictionary<string, IReadOnlyList<PluginMessage>>? Messages { get; set; }
+        public IDictionary<string, object>? OriginalInput { get; set; }
+        public IDictionary<string, object>? OriginalOutput { get; set; }
     }
 
     /// <summary>
@@ -411,15 +803,151 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
         public string? Error { get; set; }
         public string? Action { get; set; }
         public IDictionary<string, object>? Data { get; set; }
+        public string? NextPageToken { get; set; }
+        public JsonElement? Checkpoint { get; set; }
+    }
+
+    /// <summary>
+    /// Progress update sent by plugins through the report_progress host function
+    /// </summary>
+    private class ProgressReport
+    {
+        public int Percent { get; set; }
+        public string? Message { get; set; }
+    }
+
+    /// <summary>
+    /// Message sent by plugins through the publish host function
+    /// </summary>
+    private class PublishRequest
+    {
+        public string? Topic { get; set; }
+        public JsonElement? Payload { get; set; }
+    }
+
+    /// <summary>
+    /// A running WASM invocation. Host functions cannot await, so messages the plugin
+    /// publishes are queued here and delivered once the call returns, and the journey's
+    /// attachments are loaded here before the call.
+    /// </summary>
+    private record InvocationScope(string PluginName, string? JourneyId)
+    {
+        public ConcurrentQueue<PluginMessage> PendingMessages { get; } = new();
+
+        /// <summary>
+        /// Contents of the files uploaded in the journey, keyed by attachment ID
+        /// </summary>
+        public IReadOnlyDictionary<string, byte[]> Attachments { get; init; } = new Dictionary<string, byte[]>();
     }
 
-    private static IDictionary<string, object>? SanitizeInput(IDictionary<string, object>? input)
+    /// <summary>
+    /// Reads the files uploaded in a journey, which are the only ones its plugins can read
+    /// </summary>
+    private async Task<IReadOnlyDictionary<string, byte[]>> LoadAttachmentsAsync(
+        string? journeyId,
+        CancellationToken cancellationToken)
     {
-        if (input == null) return null;
+        var attachments = new Dictionary<string, byte[]>();
+        if (journeyId == null)
+        {
+            return attachments;
+        }
 
-        return input
-            .Where(kv => !SensitiveFields.Contains(kv.Key))
-            .ToDictionary(kv => kv.Key, kv => kv.Value);
+        foreach (var attachment in await _attachmentStore.ListAsync(journeyId, cancellationToken))
+        {
+            var content = await _attachmentStore.ReadAsync(attachment.Id, cancellationToken);
+            if (content != null)
+            {
+                attachments[attachment.Id] = content;
+            }
+        }
+
+        return attachments;
+    }
+
+    /// <summary>
+    /// Delivers the messages an invocation published to the message bus
+    /// </summary>
+    private async Task PublishMessagesAsync(InvocationScope invocation, CancellationToken cancellationToken)
+    {
+        while (invocation.JourneyId != null && invocation.PendingMessages.TryDequeue(out var message))
+        {
+            await _messageBus.PublishAsync(invocation.JourneyId, message, cancellationToken);
+
+            _logger.LogDebug("Plugin {PluginName} published to {Topic} in journey {JourneyId}",
+                invocation.PluginName, message.Topic, invocation.JourneyId);
+        }
+    }
+
+    /// <summary>
+    /// Host functions exposed to every WASM plugin
+    /// </summary>
+    private HostFunction[] CreateHostFunctions()
+    {
+        return new[]
+        {
+            HostFunction.FromMethod("report_progress", null, (CurrentPlugin plugin, long progressOffset) =>
+            {
+                var progress = JsonSerializer.Deserialize<ProgressReport>(plugin.ReadString(progressOffset), JsonOptions);
+                var job = CurrentJob.Value;
+                if (progress == null || job == null)
+                {
+                    return;
+                }
+
+                // Saved with the checkpoint once the invocation returns
+                job.ProgressPercent = Math.Clamp(progress.Percent, 0, 100);
+                job.ProgressMessage = progress.Message;
+
+                _logger.LogDebug("Plugin job {JobId} progress: {Percent}% {Message}",
+                    job.JobId, job.ProgressPercent, job.ProgressMessage);
+            }),
+            HostFunction.FromMethod("publish", null, (CurrentPlugin plugin, long messageOffset) =>
+            {
+                var request = JsonSerializer.Deserialize<PublishRequest>(plugin.ReadString(messageOffset), JsonOptions);
+                var invocation = CurrentInvocation.Value;
+                if (string.IsNullOrEmpty(request?.Topic) || invocation?.JourneyId == null)
+                {
+                    _logger.LogWarning("Ignoring plugin message without a topic or journey");
+                    return;
+                }
+
+                invocation.PendingMessages.Enqueue(new PluginMessage
+                {
+                    Topic = request.Topic,
+                    Payload = request.Payload,
+                    Publisher = invocation.PluginName
+                });
+            }),
+            HostFunction.FromMethod("read_attachment", null, (CurrentPlugin plugin, long idOffset) =>
+            {
+                var attachmentId = plugin.ReadString(idOffset);
+                var invocation = CurrentInvocation.Value;
+
+                // Plugins can only read files uploaded in the journey they run in
+                if (invocation == null || !invocation.Attachments.TryGetValue(attachmentId, out var content))
+                {
+                    _logger.LogWarning("Plugin {PluginName} requested unknown attachment {AttachmentId}",
+                        invocation?.PluginName, attachmentId);
+                    return 0L;
+                }
+
+                return plugin.WriteBytes(content);
+            })
+        };
+    }
+
+    /// <summary>
+    /// Flattens a page's items, which are JSON arrays for WASM plugins and lists for managed plugins
+    /// </summary>
+    private static IEnumerable<object> EnumerateItems(object? pageItems)
+    {
+        return pageItems switch
+        {
+            JsonElement { ValueKind: JsonValueKind.Array } array => array.EnumerateArray().Select(e => (object)e.Clone()),
+            IEnumerable<object> list => list,
+            _ => Enumerable.Empty<object>()
+        };
     }
 
     /// <summary>
@@ -469,6 +997,11 @@ public class ExtismPluginExecutor : IPluginExecutor, IDisposable
             plugin.Dispose();
         }
         _plugins.Clear();
+
+        if (_ownsIdempotencyStore)
+        {
+            (_idempotencyStore as IDisposable)?.Dispose();
+        }
     }
 
     private class LoadedPlugin : IDisposable
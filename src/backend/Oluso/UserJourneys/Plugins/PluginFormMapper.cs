@@ -0,0 +1,183 @@
+using System.Globalization;
+using System.Text.Json;
+using Oluso.Core.UserJourneys;
+using Oluso.UserJourneys.Steps;
+
+namespace Oluso.UserJourneys.Plugins;
+
+/// <summary>
+/// Turns the form schema returned by a plugin's require_input action into the dynamic form view model
+/// </summary>
+internal static class PluginFormMapper
+{
+    private static readonly JsonSerializerOptions JsonOptions = new(JsonSerializerDefaults.Web);
+
+    /// <summary>
+    /// Reads a <see cref="DynamicFormSchema"/> from plugin output (WASM output holds JsonElements, managed output plain objects)
+    /// </summary>
+    public static DynamicFormSchema FromOutput(IDictionary<string, object>? output)
+    {
+        if (output == null)
+        {
+            return new DynamicFormSchema();
+        }
+
+        var json = JsonSerializer.SerializeToElement(output, JsonOptions);
+        return json.Deserialize<DynamicFormSchema>(JsonOptions) ?? new DynamicFormSchema();
+    }
+
+    public static DynamicFormViewModel ToViewModel(DynamicFormSchema schema)
+    {
+        return new DynamicFormViewModel
+        {
+            Title = schema.Title,
+            Description = schema.Description,
+            FormError = schema.ErrorMessage,
+            SubmitButtonText = schema.SubmitButtonText,
+            CancelButtonText = schema.CancelButtonText,
+            ShowCancel = schema.CancelButtonText != null,
+            Fields = schema.Fields.Select(ToViewModel).ToList(),
+            Wizard = ToViewModel(schema.Wizard)
+        };
+    }
+
+    private static DynamicFormWizardViewModel? ToViewModel(DynamicFormWizard? wizard)
+    {
+        if (wizard == null)
+        {
+            return null;
+        }
+
+        var current = wizard.CurrentIndex;
+        return new DynamicFormWizardViewModel
+        {
+            Pages = wizard.Pages.Select(p => p.Title).ToList(),
+            CurrentIndex = current,
+            Progress = wizard.GetProgress(),
+            CanGoBack = wizard.CanGoBack
+        };
+    }
+
+    private static DynamicFormFieldViewModel ToViewModel(DynamicFormField field)
+    {
+        if (field.IsContent)
+        {
+            return new DynamicFormFieldViewModel
+            {
+                Name = field.Name ?? string.Empty,
+                Type = field.Type.ToLowerInvariant(),
+                Label = field.Label ?? string.Empty,
+                // Plugin markdown is untrusted: only the sanitizer's output reaches the page
+                Html = field.Content != null ? PluginContentSanitizer.RenderMarkdown(field.Content) : null,
+                Variant = field.Variant?.ToLowerInvariant() ?? "info",
+                Href = field.Href,
+                ShowWhen = ToViewModel(field.ShowIf)
+            };
+        }
+
+        var isText = field.Type is not ("number" or "date");
+        var options = field.Type is "country" or "phone"
+            ? FormCountries.Options(field.Countries)
+            : field.Options ?? new List<DynamicFormOption>();
+
+        var viewModel = new DynamicFormFieldViewModel
+        {
+            Name = field.Name,
+            Type = field.Type,
+            Label = field.Label ?? field.Name,
+            Placeholder = field.Placeholder,
+            Description = field.HelpText,
+            Required = field.Required,
+            // Secrets are never rendered back into the page
+            Value = field.IsSensitive ? null : field.Type == "country" ? field.Value ?? field.DefaultCountry?.ToUpperInvariant() : field.Value,
+            Error = field.ValidationError,
+            Options = options
+                .Select(o => new DynamicFormOptionViewModel { Value = o.Value, Label = o.Label })
+                .ToList(),
+            Pattern = field.Pattern,
+            MinLength = isText ? field.Min : null,
+            MaxLength = isText ? field.Max : null,
+            Min = field.Type == "date" ? field.MinDate : isText ? null : field.Min?.ToString(),
+            Max = field.Type == "date" ? field.MaxDate : isText ? null : field.Max?.ToString(),
+            Rows = field.Rows,
+            Accept = field.Accept,
+            DefaultCountry = field.Type == "phone" ? field.DefaultCountry?.ToUpperInvariant() : null,
+            AriaLabel = field.AriaLabel,
+            AriaDescription = field.AriaDescription,
+            Autocomplete = field.Autocomplete,
+            InputMode = field.InputMode?.ToLowerInvariant(),
+            LoadOptions = field.OptionsFunction != null,
+            Hidden = field.Type == "hidden",
+            ShowWhen = ToViewModel(field.ShowIf)
+        };
+
+        ApplyValidationRules(viewModel, field.Validation);
+
+        return viewModel;
+    }
+
+    private static DynamicFormConditionViewModel? ToViewModel(DynamicFormCondition? condition)
+    {
+        return condition != null ? new DynamicFormConditionViewModel
+        {
+            Field = condition.Field,
+            Operator = condition.Operator,
+            Value = condition.Value ?? ""
+        } : null;
+    }
+
+    /// <summary>
+    /// Maps validation rules onto the HTML constraint attributes the view renders
+    /// </summary>
+    private static void ApplyValidationRules(DynamicFormFieldViewModel viewModel, List<DynamicFormValidationRule>? rules)
+    {
+        if (rules == null)
+        {
+            return;
+        }
+
+        foreach (var rule in rules)
+        {
+            switch (rule.Type)
+            {
+                case "pattern":
+                    viewModel.Pattern = rule.Value?.GetString();
+                    break;
+                case "min_length":
+                    viewModel.MinLength = GetLength(rule.Value);
+                    break;
+                case "max_length":
+                    viewModel.MaxLength = GetLength(rule.Value);
+                    break;
+                case "min":
+                    viewModel.Min = GetNumber(rule.Value);
+                    break;
+                case "max":
+                    viewModel.Max = GetNumber(rule.Value);
+                    break;
+                case "required_if":
+                    viewModel.RequiredIf = ToViewModel(rule.Condition);
+                    break;
+            }
+
+            if (rule.Message != null)
+            {
+                viewModel.ValidationMessages ??= new Dictionary<string, string>();
+                viewModel.ValidationMessages[rule.Type] = rule.Message;
+            }
+        }
+    }
+
+    /// <summary>
+    /// Reads a length rule's value; values that are not whole numbers are left out (schema validation rejects them)
+    /// </summary>
+    private static int? GetLength(JsonElement? value) =>
+        value is { ValueKind: JsonValueKind.Number } number && number.TryGetInt32(out var length) && length >= 0
+            ? length
+            : null;
+
+    private static string? GetNumber(JsonElement? value) =>
+        value is { ValueKind: JsonValueKind.Number } number && number.TryGetDouble(out var n)
+            ? n.ToString(CultureInfo.InvariantCulture)
+            : null;
+}
@@ -0,0 +1,225 @@
+using System.Collections.Concurrent;
+using Microsoft.Extensions.DependencyInjection;
+using Microsoft.Extensions.Hosting;
+using Microsoft.Extensions.Logging;
+using Oluso.Core.UserJourneys;
+
+namespace Oluso.UserJourneys.Plugins;
+
+/// <summary>
+/// Drives long-running plugin jobs in the background using IHostedService.
+/// Jobs are queued by saving them to the IPluginJobStore as running (custom_plugin steps with
+/// <c>"job": true</c> do this); every poll starts queued jobs and resumes jobs interrupted by a
+/// restart from their last checkpoint. Jobs only survive restarts with a durable job store
+/// (UseDistributedCachePluginJobStore). Instances sharing a job store claim each job before running
+/// it and renew the claim while it runs, so a job runs on one instance at a time and is taken over
+/// once the claim of a stopped instance expires.
+/// </summary>
+public class PluginJobProcessor : BackgroundService
+{
+    private readonly IPluginExecutor _executor;
+    private readonly IPluginJobStore _jobStore;
+    private readonly IServiceProvider _serviceProvider;
+    private readonly ILogger<PluginJobProcessor> _logger;
+    private readonly PluginExecutorOptions _options;
+
+    /// <summary>
+    /// IDs of the jobs this processor is running
+    /// </summary>
+    private readonly ConcurrentDictionary<string, byte> _running = new();
+
+    /// <summary>
+    /// Owner recorded on the claims of this processor
+    /// </summary>
+    private readonly string _instanceId = $"{Environment.MachineName}:{Guid.NewGuid():N}";
+
+    public PluginJobProcessor(
+        IPluginExecutor executor,
+        IPluginJobStore jobStore,
+        IServiceProvider serviceProvider,
+        ILogger<PluginJobProcessor> logger,
+        PluginExecutorOptions? options = null)
+    {
+        _executor = executor;
+        _jobStore = jobStore;
+        _serviceProvider = serviceProvider;
+        _logger = logger;
+        _options = options ?? new PluginExecutorOptions();
+    }
+
+    /// <summary>
+    /// Starts running jobs that are not already being run, up to <see cref="PluginExecutorOptions.MaxConcurrentJobs"/>.
+    /// Jobs claimed by another instance are skipped. Jobs run in the background; cancelling
+    /// <paramref name="cancellationToken"/> pauses them at their last checkpoint.
+    /// </summary>
+    /// <returns>The number of jobs started</returns>
+    public async Task<int> StartPendingJobsAsync(CancellationToken cancellationToken = default)
+    {
+        var started = 0;
+        foreach (var job in await _jobStore.GetRunningAsync(cancellationToken))
+        {
+            if (_running.Count >= _options.MaxConcurrentJobs)
+            {
+                break;
+            }
+
+            if (!_running.TryAdd(job.JobId, 0))
+            {
+                continue;
+            }
+
+            if (!await _jobStore.TryClaimAsync(job.JobId, _instanceId, _options.JobLeaseDuration, cancellationToken))
+            {
+                _running.TryRemove(job.JobId, out _);
+                continue;
+            }
+
+            _ = RunJobAsync(job, cancellationToken);
+            started++;
+        }
+
+        return started;
+    }
+
+    protected override async Task ExecuteAsync(CancellationToken stoppingToken)
+    {
+        _logger.LogInformation(
+            "Starting plugin job processor with interval {Interval}",
+            _options.JobPollInterval);
+
+        while (!stoppingToken.IsCancellationRequested)
+        {
+            try
+            {
+                var started = await StartPendingJobsAsync(stoppingToken);
+
+                if (started > 0)
+                {
+                    _logger.LogDebug("Started {Count} plugin jobs", started);
+                }
+            }
+            catch (Exception ex) when (!stoppingToken.IsCancellationRequested)
+            {
+                _logger.LogError(ex, "Error starting plugin jobs");
+                // Continue processing after delay
+            }
+
+            try
+            {
+                await Task.Delay(_options.JobPollInterval, stoppingToken);
+            }
+            catch (OperationCanceledException)
+            {
+                // Normal shutdown; running jobs resume from their last checkpoint on the next start
+                break;
+            }
+        }
+
+        _logger.LogInformation("Plugin job processor stopped");
+    }
+
+    private async Task RunJobAsync(PluginJobState job, CancellationToken cancellationToken)
+    {
+        using var jobCts = CancellationTokenSource.CreateLinkedTokenSource(cancellationToken);
+        var renewal = RenewClaimAsync(job.JobId, jobCts);
+
+        try
+        {
+            // Run on the thread pool so a synchronous plugin call does not hold up the poll loop
+            await Task.Yield();
+
+            using var scope = _serviceProvider.CreateScope();
+            var context = await CreateContextAsync(job, scope.ServiceProvider, jobCts.Token);
+
+            await _executor.RunJobAsync(job.PluginName, job.FunctionName, job.JobId, context, jobCts.Token);
+        }
+        catch (OperationCanceledException) when (jobCts.IsCancellationRequested)
+        {
+            _logger.LogInformation("Paused plugin job {JobId} at its last checkpoint", job.JobId);
+        }
+        catch (Exception ex)
+        {
+            _logger.LogError(ex, "Error running plugin job {JobId}, retrying on the next poll", job.JobId);
+        }
+        finally
+        {
+            jobCts.Cancel();
+            await renewal;
+            await ReleaseClaimAsync(job.JobId);
+            _running.TryRemove(job.JobId, out _);
+        }
+    }
+
+    /// <summary>
+    /// Renews the claim on a running job until <paramref name="jobCts"/> is cancelled, and cancels
+    /// the job when the claim was lost to another instance
+    /// </summary>
+    private async Task RenewClaimAsync(string jobId, CancellationTokenSource jobCts)
+    {
+        var interval = _options.JobLeaseDuration / 3;
+
+        try
+        {
+            while (true)
+            {
+                await Task.Delay(interval, jobCts.Token);
+
+                if (!await _jobStore.TryClaimAsync(jobId, _instanceId, _options.JobLeaseDuration, jobCts.Token))
+                {
+                    _logger.LogWarning("Lost the claim on plugin job {JobId}, pausing it", jobId);
+                    jobCts.Cancel();
+                    return;
+                }
+            }
+        }
+        catch (OperationCanceledException)
+        {
+            // The job finished or was paused
+        }
+        catch (Exception ex)
+        {
+            _logger.LogError(ex, "Error renewing the claim on plugin job {JobId}, pausing it", jobId);
+            jobCts.Cancel();
+        }
+    }
+
+    private async Task ReleaseClaimAsync(string jobId)
+    {
+        try
+        {
+            await _jobStore.ReleaseAsync(jobId, _instanceId);
+        }
+        catch (Exception ex)
+        {
+            // The claim expires after the lease duration
+            _logger.LogWarning(ex, "Error releasing the claim on plugin job {JobId}", jobId);
+        }
+    }
+
+    /// <summary>
+    /// Rebuilds the invocation context the job was queued with. Configuration is resolved when the
+    /// job runs, so platform and tenant changes apply to resumed jobs.
+    /// </summary>
+    private static async Task<PluginExecutionContext> CreateContextAsync(
+        PluginJobState job,
+        IServiceProvider services,
+        CancellationToken cancellationToken)
+    {
+        var configResolver = services.GetService<IPluginConfigResolver>();
+        var resolvedConfig = configResolver != null
+            ? await configResolver.ResolveAsync(job.PluginName, job.TenantId, job.Config, cancellationToken)
+            : null;
+
+        return new PluginExecutionContext
+        {
+            UserId = job.UserId,
+            TenantId = job.TenantId,
+            JourneyId = job.JourneyId,
+            StepId = job.StepId,
+            IdempotencyKey = job.JobId,
+            Input = job.Input,
+            Config = resolvedConfig?.Values ?? job.Config,
+            ConfigProvenance = resolvedConfig?.Provenance
+        };
+    }
+}
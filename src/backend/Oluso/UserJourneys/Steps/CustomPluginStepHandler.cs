@@ -1,6 +1,9 @@
+using System.Text.Json;
 using Microsoft.Extensions.DependencyInjection;
 using Microsoft.Extensions.Logging;
+using Oluso.Core.Domain.Interfaces;
 using Oluso.Core.UserJourneys;
+using Oluso.UserJourneys.Plugins;
 
 namespace Oluso.UserJourneys.Steps;
 
@@ -11,6 +14,11 @@ namespace Oluso.UserJourneys.Steps;
 /// </summary>
 public class CustomPluginStepHandler : IStepHandler
 {
+    /// <summary>
+    /// Journey data key counting completed runs per step, used to derive idempotency keys
+    /// </summary>
+    public const string StepAttemptsKey = PluginExecutionContext.StepAttemptsDataKey;
+
     public string StepType => "custom_plugin";
 
     public async Task<StepHandlerResult> ExecuteAsync(StepExecutionContext context, CancellationToken cancellationToken = default)
@@ -29,19 +37,25 @@ public class CustomPluginStepHandler : IStepHandler
             return StepHandlerResult.Fail("plugin_config_error", "Plugin name not specified");
         }
 
+        if (context.GetConfig<bool>("job", false))
+        {
+            return await QueueJobAsync(pluginName, entryPoint, context, logger, cancellationToken);
+        }
+
         logger.LogDebug("Executing custom plugin {PluginName}.{EntryPoint}", pluginName, entryPoint);
 
-        // Try managed (.NET) plugins first via registry
-        var managedPlugin = managedRegistry?.Get(pluginName);
-        if (managedPlugin != null)
+        // The executor runs managed and WASM plugins alike, following page tokens and replaying
+        // the completed result of a retried step attempt
+        if (pluginExecutor != null)
         {
-            return await ExecuteManagedPluginAsync(managedPlugin, entryPoint, context, logger, cancellationToken);
+            return await ExecuteWithExecutorAsync(pluginExecutor, pluginName, entryPoint, context, logger, cancellationToken);
         }
 
-        // Try WASM plugins via executor
-        if (pluginExecutor != null)
+        // Without an executor, managed (.NET) plugins run straight from the registry
+        var managedPlugin = managedRegistry?.Get(pluginName);
+        if (managedPlugin != null)
         {
-            return await ExecuteWasmPluginAsync(pluginExecutor, pluginName, entryPoint, context, logger, cancellationToken);
+            return await ExecuteManagedPluginAsync(managedPlugin, entryPoint, context, logger, cancellationToken);
         }
 
         logger.LogError("No plugin executor or registry available");
@@ -57,19 +71,27 @@ public class CustomPluginStepHandler : IStepHandler
     {
         try
         {
-            var pluginContext = new PluginExecutionContext
-            {
-                UserId = context.UserId,
-                TenantId = context.TenantId,
-                Input = context.UserInput,
-                JourneyData = context.JourneyData
-            };
+            var pluginContext = await CreatePluginContextAsync(plugin.Name, context, cancellationToken);
 
             var result = await plugin.ExecuteAsync(entryPoint, pluginContext, cancellationToken);
 
             logger.LogDebug("Managed plugin {PluginName} returned success={Success}", plugin.Name, result.Success);
 
-            return MapPluginResult(result);
+            var messageBus = context.ServiceProvider.GetService<IPluginMessageBus>();
+            if (messageBus != null)
+            {
+                await messageBus.PublishAllAsync(context.JourneyId, plugin.Name, result.PublishedMessages, cancellationToken);
+            }
+
+            if (plugin.SupportsCompensation)
+            {
+                result = RecordCompensation(result, plugin.Name, entryPoint, context);
+            }
+
+            result = RecordAttempt(result, context);
+            result = ClearWizard(result, context);
+
+            return await MapPluginResultAsync(result, plugin.Name, context, pluginContext.JourneyData, logger, cancellationToken);
         }
         catch (Exception ex)
         {
@@ -78,7 +100,7 @@ public class CustomPluginStepHandler : IStepHandler
         }
     }
 
-    private async Task<StepHandlerResult> ExecuteWasmPluginAsync(
+    private async Task<StepHandlerResult> ExecuteWithExecutorAsync(
         IPluginExecutor executor,
         string pluginName,
         string entryPoint,
@@ -88,29 +110,229 @@ public class CustomPluginStepHandler : IStepHandler
     {
         try
         {
-            var pluginContext = new PluginExecutionContext
-            {
-                UserId = context.UserId,
-                TenantId = context.TenantId,
-                Input = context.UserInput,
-                JourneyData = context.JourneyData
-            };
+            var pluginContext = await CreatePluginContextAsync(pluginName, context, cancellationToken);
 
-            var result = await executor.ExecuteAsync(pluginName, entryPoint, pluginContext, cancellationToken);
+            // With allPages the step output holds the items of every page
+            var result = context.GetConfig<bool>("allPages", false)
+                ? await executor.ExecuteAllPagesAsync(pluginName, entryPoint, pluginContext, cancellationToken)
+                : await executor.ExecuteAsync(pluginName, entryPoint, pluginContext, cancellationToken);
 
-            logger.LogDebug("WASM plugin {PluginName} returned success={Success}, action={Action}",
+            logger.LogDebug("Plugin {PluginName} returned success={Success}, action={Action}",
                 pluginName, result.Success, result.Action);
 
-            return MapPluginResult(result);
+            if (executor.SupportsCompensation(pluginName))
+            {
+                result = RecordCompensation(result, pluginName, entryPoint, context);
+            }
+
+            result = RecordAttempt(result, context);
+            result = ClearWizard(result, context);
+
+            return await MapPluginResultAsync(result, pluginName, context, pluginContext.JourneyData, logger, cancellationToken);
         }
         catch (Exception ex)
         {
-            logger.LogError(ex, "Error executing WASM plugin {PluginName}", pluginName);
+            logger.LogError(ex, "Error executing plugin {PluginName}", pluginName);
             return StepHandlerResult.Fail("plugin_error", $"Plugin execution failed: {ex.Message}");
         }
     }
 
-    private static StepHandlerResult MapPluginResult(PluginExecutionResult result)
+    /// <summary>
+    /// Queues the plugin function as a long-running job for the PluginJobProcessor and continues the
+    /// journey straight away, with the job ID in the step output. The job ID is the step attempt's
+    /// idempotency key, so a retried step finds its job instead of queuing another.
+    /// </summary>
+    private static async Task<StepHandlerResult> QueueJobAsync(
+        string pluginName,
+        string entryPoint,
+        StepExecutionContext context,
+        ILogger logger,
+        CancellationToken cancellationToken)
+    {
+        var jobStore = context.ServiceProvider.GetService<IPluginJobStore>();
+        if (jobStore == null)
+        {
+            logger.LogError("No plugin job store available for job step {StepId}", context.StepId);
+            return StepHandlerResult.Fail("plugin_unavailable", "Plugin jobs not configured");
+        }
+
+        var jobId = PluginExecutionContext.CreateIdempotencyKey(
+            context.JourneyId, context.StepId, GetStepAttempts(context.JourneyData).GetValueOrDefault(context.StepId));
+
+        if (await jobStore.GetAsync(jobId, cancellationToken) == null)
+        {
+            await jobStore.SaveAsync(new PluginJobState
+            {
+                JobId = jobId,
+                PluginName = pluginName,
+                FunctionName = entryPoint,
+                TenantId = context.TenantId,
+                UserId = context.UserId,
+                JourneyId = context.JourneyId,
+                StepId = context.StepId,
+                Input = PluginInputSanitizer.SanitizeExcluding(
+                    context.UserInput, DynamicFormSchema.GetSensitiveFields(context.JourneyData)),
+                Config = context.GetConfig<Dictionary<string, object>>("config", null)
+            }, cancellationToken);
+
+            logger.LogInformation("Queued plugin job {JobId} ({PluginName}.{EntryPoint})", jobId, pluginName, entryPoint);
+        }
+
+        var result = RecordAttempt(new PluginExecutionResult
+        {
+            Success = true,
+            Output = new Dictionary<string, object> { [PluginJobState.JourneyDataKey] = jobId }
+        }, context);
+
+        return StepHandlerResult.Success(result.Output);
+    }
+
+    private static async Task<PluginExecutionContext> CreatePluginContextAsync(
+        string pluginName,
+        StepExecutionContext context,
+        CancellationToken cancellationToken)
+    {
+        var stepConfig = context.GetConfig<Dictionary<string, object>>("config", null);
+        var configResolver = context.ServiceProvider.GetService<IPluginConfigResolver>();
+        var resolvedConfig = configResolver != null
+            ? await configResolver.ResolveAsync(pluginName, context.TenantId, stepConfig, cancellationToken)
+            : null;
+
+        // Only the masked fields of this step's forms may reach its plugin; masked fields of other steps
+        // are kept from it like any other sensitive value
+        var sensitiveFields = DynamicFormSchema.GetSensitiveFields(context.JourneyData, context.StepId);
+
+        // Messages on the subscribed topics are delivered the same way to managed and WASM plugins
+        var subscriptions = context.GetConfig<List<string>>("subscribe", null);
+        var messageBus = context.ServiceProvider.GetService<IPluginMessageBus>();
+        var messages = messageBus != null && subscriptions is { Count: > 0 }
+            ? await messageBus.GetMessagesAsync(context.JourneyId, subscriptions, cancellationToken)
+            : null;
+
+        // A submission of a wizard page moves the wizard before the plugin sees it; re-rendering the
+        // current page (e.g. a refresh) is not a submission
+        var journeyData = context.JourneyData;
+        var wizards = PluginWizardState.FromJourneyData(journeyData);
+        if (!string.IsNullOrEmpty(context.GetInput("__submitted")) && wizards.TryGetValue(context.StepId, out var wizard))
+        {
+            wizard.Submit(context.UserInput, DynamicFormSchema.GetSensitiveFields(context.JourneyData));
+            journeyData = new Dictionary<string, object>(journeyData) { [PluginWizardState.JourneyDataKey] = wizards };
+        }
+
+        return new PluginExecutionContext
+        {
+            UserId = context.UserId,
+            TenantId = context.TenantId,
+            JourneyId = context.JourneyId,
+            StepId = context.StepId,
+            IdempotencyKey = PluginExecutionContext.CreateIdempotencyKey(
+                context.JourneyId, context.StepId, GetStepAttempts(context.JourneyData).GetValueOrDefault(context.StepId)),
+            Input = context.UserInput,
+            JourneyData = journeyData,
+            Config = resolvedConfig?.Values ?? stepConfig,
+            ConfigProvenance = resolvedConfig?.Provenance,
+            Subscriptions = subscriptions,
+            Messages = messages,
+            SensitiveInputFields = sensitiveFields
+        };
+    }
+
+    /// <summary>
+    /// Appends a successful step to the journey's compensation log (carried in the step output)
+    /// so it can be undone if the journey fails later
+    /// </summary>
+    private static PluginExecutionResult RecordCompensation(
+        PluginExecutionResult result,
+        string pluginName,
+        string entryPoint,
+        StepExecutionContext context)
+    {
+        if (!result.IsCompleted)
+        {
+            return result;
+        }
+
+        var entries = PluginCompensationEntry.FromJourneyData(context.JourneyData);
+        entries.Add(new PluginCompensationEntry
+        {
+            StepId = context.StepId,
+            PluginName = pluginName,
+            FunctionName = entryPoint,
+            Input = PluginInputSanitizer.SanitizeExcluding(
+                context.UserInput, DynamicFormSchema.GetSensitiveFields(context.JourneyData)),
+            Output = result.Output
+        });
+
+        return WithOutputValue(result, PluginCompensationEntry.JourneyDataKey, entries);
+    }
+
+    /// <summary>
+    /// Counts a completed run of the step so a later run of the same step gets a new idempotency key
+    /// </summary>
+    private static PluginExecutionResult RecordAttempt(PluginExecutionResult result, StepExecutionContext context)
+    {
+        if (!result.IsCompleted)
+        {
+            return result;
+        }
+
+        var attempts = GetStepAttempts(context.JourneyData);
+        attempts[context.StepId] = attempts.GetValueOrDefault(context.StepId) + 1;
+
+        return WithOutputValue(result, StepAttemptsKey, attempts);
+    }
+
+    /// <summary>
+    /// Forgets the step's wizard position once the step completes, so a later run starts on the first page
+    /// </summary>
+    private static PluginExecutionResult ClearWizard(PluginExecutionResult result, StepExecutionContext context)
+    {
+        var wizards = PluginWizardState.FromJourneyData(context.JourneyData);
+        if (!result.IsCompleted || !wizards.Remove(context.StepId))
+        {
+            return result;
+        }
+
+        return WithOutputValue(result, PluginWizardState.JourneyDataKey, wizards);
+    }
+
+    private static Dictionary<string, int> GetStepAttempts(IDictionary<string, object> journeyData)
+    {
+        if (journeyData.TryGetValue(StepAttemptsKey, out var value))
+        {
+            if (value is Dictionary<string, int> attempts)
+                return new Dictionary<string, int>(attempts);
+            if (value is JsonElement { ValueKind: JsonValueKind.Object } element)
+                return element.Deserialize<Dictionary<string, int>>() ?? new Dictionary<string, int>();
+        }
+        return new Dictionary<string, int>();
+    }
+
+    private static PluginExecutionResult WithOutputValue(PluginExecutionResult result, string key, object value)
+    {
+        var output = result.Output != null
+            ? new Dictionary<string, object>(result.Output)
+            : new Dictionary<string, object>();
+        output[key] = value;
+
+        return new PluginExecutionResult
+        {
+            Success = result.Success,
+            Error = result.Error,
+            Output = output,
+            Action = result.Action,
+            NextPageToken = result.NextPageToken,
+            Checkpoint = result.Checkpoint
+        };
+    }
+
+    private static async Task<StepHandlerResult> MapPluginResultAsync(
+        PluginExecutionResult result,
+        string pluginName,
+        StepExecutionContext context,
+        IDictionary<string, object>? journeyData,
+        ILogger logger,
+        CancellationToken cancellationToken)
     {
         if (!result.Success)
         {
@@ -121,7 +343,7 @@ public class CustomPluginStepHandler : IStepHandler
         {
             PluginAction.Continue => StepHandlerResult.Success(result.Output),
             PluginAction.Complete => StepHandlerResult.Success(result.Output),
-            PluginAction.RequireInput => StepHandlerResult.ShowUi("Journey/_DynamicForm", result.Output),
+            PluginAction.RequireInput => await ShowPluginFormAsync(result.Output, pluginName, context, journeyData, logger, cancellationToken),
             PluginAction.Branch => result.Output?.TryGetValue("branchId", out var branchId) == true
                 ? StepHandlerResult.Branch(branchId?.ToString() ?? "default", result.Output)
                 : StepHandlerResult.Success(result.Output),
@@ -129,4 +351,103 @@ public class CustomPluginStepHandler : IStepHandler
             _ => StepHandlerResult.Success(result.Output)
         };
     }
+
+    /// <summary>
+    /// Renders the plugin's form schema after checking its show_if references. Masked fields are
+    /// remembered in journey data so their values reach the plugin on submit but are kept out of
+    /// logs and submissions; options functions and file fields are remembered so the form can call
+    /// them and upload to them.
+    /// Branding hints are applied only as far as the tenant's plugin branding allows. The wizard position
+    /// (from <paramref name="journeyData"/>, which includes the submission the plugin just handled) is saved
+    /// for the next submission.
+    /// </summary>
+    private static async Task<StepHandlerResult> ShowPluginFormAsync(
+        IDictionary<string, object>? output,
+        string pluginName,
+        StepExecutionContext context,
+        IDictionary<string, object>? journeyData,
+        ILogger logger,
+        CancellationToken cancellationToken)
+    {
+        var schema = PluginFormMapper.FromOutput(output);
+
+        var schemaErrors = schema.Validate();
+        if (schemaErrors.Count > 0)
+        {
+            return StepHandlerResult.Fail("plugin_form_invalid", string.Join("; ", schemaErrors));
+        }
+
+        var viewModel = PluginFormMapper.ToViewModel(schema);
+
+        if (schema.Branding != null)
+        {
+            var settingsProvider = context.ServiceProvider.GetService<ITenantSettingsProvider>();
+            var allowed = settingsProvider != null
+                ? await settingsProvider.GetSettingsAsync<PluginBrandingSettings>(cancellationToken)
+                : null;
+
+            var branding = schema.Branding.Resolve(allowed ?? PluginBrandingSettings.Default, out var rejected);
+            if (rejected.Count > 0)
+            {
+                logger.LogWarning("Ignoring branding not allowed for tenant {TenantId} in plugin {PluginName}: {Rejected}",
+                    context.TenantId, pluginName, string.Join(", ", rejected));
+            }
+
+            viewModel.LogoUrl = branding.LogoUrl;
+            viewModel.AccentColor = branding.AccentColor;
+            viewModel.Layout = branding.Layout;
+        }
+
+        var outputData = new Dictionary<string, object>();
+
+        var sensitiveFields = schema.Fields.Where(f => f.IsSensitive).Select(f => f.Name).ToList();
+        if (sensitiveFields.Count > 0)
+        {
+            var recorded = DynamicFormSchema.GetSensitiveFieldsByStep(context.JourneyData);
+            if (!recorded.TryGetValue(context.StepId, out var stepFields))
+            {
+                recorded[context.StepId] = stepFields = new HashSet<string>(StringComparer.OrdinalIgnoreCase);
+            }
+            stepFields.UnionWith(sensitiveFields);
+            outputData[DynamicFormSchema.SensitiveFieldsDataKey] = recorded.ToDictionary(s => s.Key, s => s.Value.ToList());
+        }
+
+        // Options sources only apply to the form currently shown
+        var optionSources = schema.Fields
+            .Where(f => f.OptionsFunction != null)
+            .ToDictionary(f => f.Name, f => new PluginFieldOptionsSource
+            {
+                PluginName = pluginName,
+                FunctionName = f.OptionsFunction!,
+                StepId = context.StepId
+            });
+        if (optionSources.Count > 0 || context.JourneyData.ContainsKey(PluginFieldOptionsSource.JourneyDataKey))
+        {
+            outputData[PluginFieldOptionsSource.JourneyDataKey] = optionSources;
+        }
+
+        // Uploads are only accepted for the file fields of the form currently shown
+        var fileFields = schema.Fields
+            .Where(f => f.Type == "file")
+            .ToDictionary(f => f.Name, PluginFileField.FromField);
+        if (fileFields.Count > 0 || context.JourneyData.ContainsKey(PluginFileField.JourneyDataKey))
+        {
+            outputData[PluginFileField.JourneyDataKey] = fileFields;
+        }
+
+        var wizards = PluginWizardState.FromJourneyData(journeyData);
+        if (schema.Wizard != null)
+        {
+            var wizard = wizards.GetValueOrDefault(context.StepId) ?? new PluginWizardState();
+            wizard.Show(schema.Wizard);
+            wizards[context.StepId] = wizard;
+            outputData[PluginWizardState.JourneyDataKey] = wizards;
+        }
+        else if (wizards.Remove(context.StepId))
+        {
+            outputData[PluginWizardState.JourneyDataKey] = wizards;
+        }
+
+        return StepHandlerResult.ShowUi("Journey/_DynamicForm", viewModel, outputData.Count > 0 ? outputData : null);
+    }
 }
@@ -462,6 +462,22 @@ public class DynamicFormViewModel
     /// Global form-level error message (e.g., duplicate submission)
     /// </summary>
     public string? FormError { get; set; }
+    /// <summary>
+    /// Logo replacing the journey's logo (plugin branding allowed by the tenant)
+    /// </summary>
+    public string? LogoUrl { get; set; }
+    /// <summary>
+    /// Accent color (#rrggbb) replacing the journey's primary color
+    /// </summary>
+    public string? AccentColor { get; set; }
+    /// <summary>
+    /// Layout width replacing the journey's layout (narrow, medium, wide, full)
+    /// </summary>
+    public string? Layout { get; set; }
+    /// <summary>
+    /// Stepper shown when the form is one page of a plugin wizard
+    /// </summary>
+    public DynamicFormWizardViewModel? Wizard { get; set; }
 }
 
 public class DynamicFormFieldViewModel
@@ -486,6 +502,54 @@ public class DynamicFormFieldViewModel
     public bool Hidden { get; set; }
     public string? Group { get; set; }
     public DynamicFormConditionViewModel? ShowWhen { get; set; }
+    /// <summary>
+    /// Accepted content types for file fields
+    /// </summary>
+    public string? Accept { get; set; }
+    /// <summary>
+    /// Select options are fetched on demand from a plugin options function
+    /// </summary>
+    public bool LoadOptions { get; set; }
+    /// <summary>
+    /// The field is required while this condition holds
+    /// </summary>
+    public DynamicFormConditionViewModel? RequiredIf { get; set; }
+    /// <summary>
+    /// Custom client-side error messages keyed by rule (pattern, min_length, max_length, min, max, required_if)
+    /// </summary>
+    public Dictionary<string, string>? ValidationMessages { get; set; }
+    /// <summary>
+    /// Country preselected next to a phone field (submitted as {Name}_country)
+    /// </summary>
+    public string? DefaultCountry { get; set; }
+    /// <summary>
+    /// Accessible name overriding the visible label for screen readers
+    /// </summary>
+    public string? AriaLabel { get; set; }
+    /// <summary>
+    /// Screen-reader-only description, referenced by aria-describedby
+    /// </summary>
+    public string? AriaDescription { get; set; }
+    /// <summary>
+    /// autocomplete attribute, overriding the type's default
+    /// </summary>
+    public string? Autocomplete { get; set; }
+    /// <summary>
+    /// inputmode attribute, overriding the type's default
+    /// </summary>
+    public string? InputMode { get; set; }
+    /// <summary>
+    /// Sanitized markup of a markdown or alert content block
+    /// </summary>
+    public string? Html { get; set; }
+    /// <summary>
+    /// Style of an alert content block (info, success, warning, danger)
+    /// </summary>
+    public string? Variant { get; set; }
+    /// <summary>
+    /// Target of a link content block
+    /// </summary>
+    public string? Href { get; set; }
 }
 
 public class DynamicFormOptionViewModel
@@ -501,4 +565,21 @@ public class DynamicFormConditionViewModel
     public string Value { get; set; } = null!;
 }
 
+public class DynamicFormWizardViewModel
+{
+    /// <summary>
+    /// Page titles in order
+    /// </summary>
+    public List<string> Pages { get; set; } = new();
+    public int CurrentIndex { get; set; }
+    /// <summary>
+    /// Completion percentage (0-100)
+    /// </summary>
+    public int Progress { get; set; }
+    /// <summary>
+    /// Shows a back button that submits without validating the page
+    /// </summary>
+    public bool CanGoBack { get; set; }
+}
+
 #endregion
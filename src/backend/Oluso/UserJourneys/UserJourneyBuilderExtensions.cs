@@ -284,6 +284,18 @@ public class UserJourneyBuilder
         return this;
     }
 
+    /// <summary>
+    /// Use distributed cache (Redis, SQL, etc.) for long-running plugin jobs, so running jobs
+    /// resume from their last checkpoint after a restart.
+    /// Requires IDistributedCache to be configured (e.g., AddStackExchangeRedisCache).
+    /// </summary>
+    public UserJourneyBuilder UseDistributedCachePluginJobStore()
+    {
+        RemoveService<IPluginJobStore>();
+        Services.AddSingleton<IPluginJobStore, DistributedCachePluginJobStore>();
+        return this;
+    }
+
     /// <summary>
     /// Use a custom policy store (e.g., database).
     /// This replaces the default in-memory store.
@@ -220,6 +220,196 @@ public class DefaultJourneyOrchestratorTests
         result.Error.Should().Be("journey_not_found");
     }
 
+    [Fact]
+    public async Task CancelJourneyAsync_ClearsUploadedAttachmentsAndPluginMessages()
+    {
+        // Arrange
+        _stateStoreMock
+            .Setup(x => x.GetAsync("journey123", It.IsAny<CancellationToken>()))
+            .ReturnsAsync(new JourneyState
+            {
+                Id = "journey123",
+                PolicyId = "signin",
+                CurrentStepId = "step1",
+                Status = JourneyStatus.InProgress,
+                TenantId = "default",
+                ClientId = "test-client"
+            });
+
+        var attachmentStoreMock = new Mock<IPluginAttachmentStore>();
+        _serviceProviderMock
+            .Setup(x => x.GetService(typeof(IPluginAttachmentStore)))
+            .Returns(attachmentStoreMock.Object);
+
+        var messageBusMock = new Mock<IPluginMessageBus>();
+        _serviceProviderMock
+            .Setup(x => x.GetService(typeof(IPluginMessageBus)))
+            .Returns(messageBusMock.Object);
+
+        var orchestrator = CreateOrchestrator();
+
+        // Act
+        await orchestrator.CancelJourneyAsync("journey123");
+
+        // Assert
+        attachmentStoreMock.Verify(x => x.ClearAsync("journey123", It.IsAny<CancellationToken>()), Times.Once);
+        messageBusMock.Verify(x => x.ClearAsync("journey123", It.IsAny<CancellationToken>()), Times.Once);
+    }
+
+    [Fact]
+    public async Task CancelJourneyAsync_CompensatesPluginStepsNewestFirst()
+    {
+        // Arrange
+        _stateStoreMock
+            .Setup(x => x.GetAsync("journey123", It.IsAny<CancellationToken>()))
+            .ReturnsAsync(CreateStateWithCompensations(DateTime.UtcNow.AddMinutes(30)));
+
+        var compensated = new List<string>();
+        var executorMock = CreateCompensatingExecutor(compensated);
+
+        var orchestrator = CreateOrchestrator();
+
+        // Act
+        await orchestrator.CancelJourneyAsync("journey123");
+
+        // Assert
+        compensated.Should().Equal("create_mailbox", "provision_account");
+        executorMock.Verify(x => x.CompensateAsync(
+            "hello-plugin",
+            "provision_account",
+            It.Is<PluginExecutionContext>(c => c.JourneyId == "journey123" && c.Input!.ContainsKey("email")),
+            It.Is<IDictionary<string, object>?>(o => o!.ContainsKey("account_id")),
+            It.IsAny<CancellationToken>()), Times.Once);
+    }
+
+    [Fact]
+    public async Task ContinueJourneyAsync_WithExpiredJourney_CompensatesPluginSteps()
+    {
+        // Arrange
+        _stateStoreMock
+            .Setup(x => x.GetAsync("journey123", It.IsAny<CancellationToken>()))
+            .ReturnsAsync(CreateStateWithCompensations(DateTime.UtcNow.AddMinutes(-5)));
+
+        var compensated = new List<string>();
+        CreateCompensatingExecutor(compensated);
+
+        var orchestrator = CreateOrchestrator();
+
+        // Act
+        var result = await orchestrator.ContinueJourneyAsync("journey123", new JourneyStepInput { StepId = "step1" });
+
+        // Assert
+        result.Status.Should().Be(JourneyStatus.Expired);
+        compensated.Should().Equal("create_mailbox", "provision_account");
+    }
+
+    [Fact]
+    public async Task ContinueJourneyAsync_WhenStepFailsWithOnFailure_DoesNotCompensatePluginSteps()
+    {
+        // Arrange
+        _stateStoreMock
+            .Setup(x => x.GetAsync("journey123", It.IsAny<CancellationToken>()))
+            .ReturnsAsync(() => CreateStateWithCompensations(DateTime.UtcNow.AddMinutes(30)));
+
+        _policyStoreMock
+            .Setup(x => x.GetByIdAsync("signin", It.IsAny<CancellationToken>()))
+            .ReturnsAsync(new JourneyPolicy
+            {
+                Id = "signin",
+                Name = "Test SignIn Policy",
+                Type = JourneyType.SignIn,
+                Enabled = true,
+                Steps = new List<JourneyPolicyStep>
+                {
+                    new() { Id = "step3", Type = "custom_plugin", Order = 3, OnFailure = "recover" },
+                    new() { Id = "recover", Type = "recover_account", Order = 4 }
+                }
+            });
+
+        var failingHandler = new Mock<IStepHandler>();
+        failingHandler
+            .Setup(h => h.ExecuteAsync(It.IsAny<StepExecutionContext>(), It.IsAny<CancellationToken>()))
+            .ReturnsAsync(StepHandlerResult.Fail("plugin_error", "Directory unavailable"));
+
+        var recoveryHandler = new Mock<IStepHandler>();
+        recoveryHandler
+            .Setup(h => h.ExecuteAsync(It.IsAny<StepExecutionContext>(), It.IsAny<CancellationToken>()))
+            .ReturnsAsync(new StepHandlerResult
+            {
+                Outcome = StepOutcome.RequireInput,
+                StepResult = new JourneyStepResult
+                {
+                    StepId = "recover",
+                    StepType = "recover_account",
+                    ViewName = "_RecoverAccount"
+                }
+            });
+
+        _stepRegistryMock.Setup(x => x.GetHandler("custom_plugin")).Returns(failingHandler.Object);
+        _stepRegistryMock.Setup(x => x.GetHandler("recover_account")).Returns(recoveryHandler.Object);
+
+        var compensated = new List<string>();
+        CreateCompensatingExecutor(compensated);
+
+        var orchestrator = CreateOrchestrator();
+
+        // Act
+        var result = await orchestrator.ContinueJourneyAsync("journey123", new JourneyStepInput { StepId = "step3" });
+
+        // Assert
+        result.Status.Should().Be(JourneyStatus.InProgress);
+        result.CurrentStep!.StepId.Should().Be("recover");
+        compensated.Should().BeEmpty();
+    }
+
+    private static JourneyState CreateStateWithCompensations(DateTime expiresAt) => new()
+    {
+        Id = "journey123",
+        PolicyId = "signin",
+        CurrentStepId = "step3",
+        Status = JourneyStatus.InProgress,
+        ExpiresAt = expiresAt,
+        TenantId = "default",
+        ClientId = "test-client",
+        Data = new Dictionary<string, object>
+        {
+            [PluginCompensationEntry.JourneyDataKey] = new List<PluginCompensationEntry>
+            {
+                new()
+                {
+                    StepId = "step1",
+                    PluginName = "hello-plugin",
+                    FunctionName = "provision_account",
+                    Input = new Dictionary<string, object> { ["email"] = "ada@example.com" },
+                    Output = new Dictionary<string, object> { ["account_id"] = "acct-1" }
+                },
+                new()
+                {
+                    StepId = "step2",
+                    PluginName = "hello-plugin",
+                    FunctionName = "create_mailbox"
+                }
+            }
+        }
+    };
+
+    private Mock<IPluginExecutor> CreateCompensatingExecutor(List<string> compensated)
+    {
+        var executorMock = new Mock<IPluginExecutor>();
+        executorMock
+            .Setup(x => x.CompensateAsync(It.IsAny<string>(), It.IsAny<string>(), It.IsAny<PluginExecutionContext>(),
+                It.IsAny<IDictionary<string, object>?>(), It.IsAny<CancellationToken>()))
+            .Callback<string, string, PluginExecutionContext, IDictionary<string, object>?, CancellationToken>(
+                (_, functionName, _, _, _) => compensated.Add(functionName))
+            .ReturnsAsync(new PluginExecutionResult { Success = true });
+
+        _serviceProviderMock
+            .Setup(x => x.GetService(typeof(IPluginExecutor)))
+            .Returns(executorMock.Object);
+
+        return executorMock;
+    }
+
     private DefaultJourneyOrchestrator CreateOrchestrator()
     {
         return new DefaultJourneyOrchestrator(
@@ -0,0 +1,130 @@
+using System.Text.Json;
+using FluentAssertions;
+using Microsoft.Extensions.Logging;
+using Moq;
+using Oluso.Core.Domain.Entities;
+using Oluso.Core.Domain.Interfaces;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class DefaultPluginConfigResolverTests
+{
+    private readonly Mock<IPluginStore> _pluginStoreMock = new();
+    private readonly List<PluginMetadata> _registrations = new();
+
+    public DefaultPluginConfigResolverTests()
+    {
+        _pluginStoreMock
+            .Setup(x => x.GetAvailablePluginsAsync(It.IsAny<string?>(), It.IsAny<CancellationToken>()))
+            .ReturnsAsync(() => _registrations);
+    }
+
+    [Fact]
+    public async Task ResolveAsync_LayersPlatformTenantAndStepConfig()
+    {
+        Register(null, "{ \"greeting\": \"hello\", \"limits\": { \"maxAttempts\": 3, \"window\": 60 } }");
+        Register("tenant-1", "{ \"limits\": { \"maxAttempts\": 5 } }");
+
+        var resolved = await CreateResolver().ResolveAsync("hello-plugin", "tenant-1", new Dictionary<string, object>
+        {
+            ["limits"] = new Dictionary<string, object> { ["window"] = 30 }
+        });
+
+        Value(resolved, "greeting").GetString().Should().Be("hello");
+        Value(resolved, "limits").GetProperty("maxAttempts").GetInt32().Should().Be(5);
+        Value(resolved, "limits").GetProperty("window").GetInt32().Should().Be(30);
+
+        resolved.Provenance.Should().BeEquivalentTo(new Dictionary<string, string>
+        {
+            ["greeting"] = PluginConfigLayers.Platform,
+            ["limits.maxAttempts"] = PluginConfigLayers.Tenant,
+            ["limits.window"] = PluginConfigLayers.Step
+        });
+    }
+
+    [Fact]
+    public async Task ResolveAsync_ReplacesArraysInsteadOfMergingThem()
+    {
+        Register(null, "{ \"regions\": [\"eu\", \"us\"] }");
+        Register("tenant-1", "{ \"regions\": [\"eu\"] }");
+
+        var resolved = await CreateResolver().ResolveAsync("hello-plugin", "tenant-1", null);
+
+        Value(resolved, "regions").EnumerateArray().Select(r => r.GetString()).Should().Equal("eu");
+        resolved.Provenance["regions"].Should().Be(PluginConfigLayers.Tenant);
+    }
+
+    [Fact]
+    public async Task ResolveAsync_WhenObjectIsReplaced_DropsProvenanceOfItsChildren()
+    {
+        Register(null, "{ \"limits\": { \"maxAttempts\": 3 } }");
+
+        var resolved = await CreateResolver().ResolveAsync("hello-plugin", null, new Dictionary<string, object>
+        {
+            ["limits"] = "off"
+        });
+
+        Value(resolved, "limits").GetString().Should().Be("off");
+        resolved.Provenance.Should().ContainSingle()
+            .Which.Should().Be(new KeyValuePair<string, string>("limits", PluginConfigLayers.Step));
+    }
+
+    [Fact]
+    public async Task ResolveAsync_IgnoresOtherPluginsAndOtherTenants()
+    {
+        Register(null, "{ \"greeting\": \"hello\" }");
+        Register("tenant-2", "{ \"greeting\": \"hej\" }");
+        Register("tenant-1", "{ \"greeting\": \"hallo\" }", pluginName: "other-plugin");
+
+        var resolved = await CreateResolver().ResolveAsync("hello-plugin", "tenant-1", null);
+
+        Value(resolved, "greeting").GetString().Should().Be("hello");
+        resolved.Provenance["greeting"].Should().Be(PluginConfigLayers.Platform);
+    }
+
+    [Fact]
+    public async Task ResolveAsync_IgnoresInvalidLayer()
+    {
+        Register(null, "{ \"greeting\": \"hello\" }");
+        Register("tenant-1", "{ not json");
+
+        var resolved = await CreateResolver().ResolveAsync("hello-plugin", "tenant-1", null);
+
+        Value(resolved, "greeting").GetString().Should().Be("hello");
+        resolved.Provenance.Should().ContainKey("greeting").WhoseValue.Should().Be(PluginConfigLayers.Platform);
+    }
+
+    [Fact]
+    public async Task ResolveAsync_WithoutPluginStore_UsesStepOverridesOnly()
+    {
+        var resolver = new DefaultPluginConfigResolver(Mock.Of<ILogger<DefaultPluginConfigResolver>>());
+
+        var resolved = await resolver.ResolveAsync("hello-plugin", "tenant-1", new Dictionary<string, object>
+        {
+            ["pageSize"] = 50
+        });
+
+        Value(resolved, "pageSize").GetInt32().Should().Be(50);
+        resolved.Provenance.Should().BeEquivalentTo(new Dictionary<string, string>
+        {
+            ["pageSize"] = PluginConfigLayers.Step
+        });
+    }
+
+    private DefaultPluginConfigResolver CreateResolver() =>
+        new(Mock.Of<ILogger<DefaultPluginConfigResolver>>(), _pluginStoreMock.Object);
+
+    private void Register(string? tenantId, string defaultConfig, string pluginName = "hello-plugin") =>
+        _registrations.Add(new PluginMetadata
+        {
+            Name = pluginName,
+            TenantId = tenantId,
+            StorageReference = $"{pluginName}.wasm",
+            DefaultConfig = defaultConfig
+        });
+
+    private static JsonElement Value(ResolvedPluginConfig resolved, string key) =>
+        resolved.Values[key].Should().BeOfType<JsonElement>().Subject;
+}
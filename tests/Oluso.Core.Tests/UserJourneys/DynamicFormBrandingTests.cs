@@ -0,0 +1,69 @@
+using FluentAssertions;
+using Oluso.Core.Domain.Interfaces;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class DynamicFormBrandingTests
+{
+    private static readonly PluginBrandingSettings Allowed = new()
+    {
+        Logos = new Dictionary<string, string> { ["partner-a"] = "https://cdn.example.com/partner-a.png" },
+        AccentColors = new List<string> { "#1A73E8" },
+        Layouts = new List<string> { "narrow", "wide" }
+    };
+
+    [Fact]
+    public void Resolve_KeepsAllowedHints()
+    {
+        var branding = new DynamicFormBranding { Logo = "partner-a", AccentColor = "#1a73e8", Layout = "Wide" };
+
+        var resolved = branding.Resolve(Allowed, out var rejected);
+
+        resolved.LogoUrl.Should().Be("https://cdn.example.com/partner-a.png");
+        resolved.AccentColor.Should().Be("#1a73e8");
+        resolved.Layout.Should().Be("wide");
+        rejected.Should().BeEmpty();
+    }
+
+    [Fact]
+    public void Resolve_DropsHintsTheTenantDoesNotAllow()
+    {
+        var branding = new DynamicFormBranding { Logo = "partner-b", AccentColor = "#ff0000", Layout = "full" };
+
+        var resolved = branding.Resolve(Allowed, out var rejected);
+
+        resolved.LogoUrl.Should().BeNull();
+        resolved.AccentColor.Should().BeNull();
+        resolved.Layout.Should().BeNull();
+        rejected.Should().Equal("logo 'partner-b'", "accent color '#ff0000'", "layout 'full'");
+    }
+
+    [Fact]
+    public void Resolve_WithDefaultSettings_AllowsOnlyLayouts()
+    {
+        var branding = new DynamicFormBranding { Logo = "partner-a", AccentColor = "#1a73e8", Layout = "full" };
+
+        var resolved = branding.Resolve(PluginBrandingSettings.Default, out var rejected);
+
+        resolved.LogoUrl.Should().BeNull();
+        resolved.AccentColor.Should().BeNull();
+        resolved.Layout.Should().Be("full");
+        rejected.Should().HaveCount(2);
+    }
+
+    [Fact]
+    public void Validate_RejectsMalformedBranding()
+    {
+        var schema = new DynamicFormSchema
+        {
+            Branding = new DynamicFormBranding { AccentColor = "blue", Layout = "huge" }
+        };
+
+        var errors = schema.Validate();
+
+        errors.Should().Contain("Branding accent color 'blue' is not a #rrggbb color");
+        errors.Should().Contain("Branding layout 'huge' is not supported");
+    }
+}
@@ -0,0 +1,192 @@
+using System.Text.Json;
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class DynamicFormSchemaTests
+{
+    [Fact]
+    public void Validate_WithValidRules_ReturnsNoErrors()
+    {
+        var schema = Schema(
+            Rule("pattern", "[0-9]{6}"),
+            Rule("min_length", 6),
+            Rule("max_length", 6),
+            Rule("min", 0.5),
+            Rule("max", 100));
+
+        schema.Validate().Should().BeEmpty();
+    }
+
+    [Theory]
+    [InlineData("min_length", 3.5)]
+    [InlineData("max_length", 3.5)]
+    [InlineData("min_length", -1)]
+    [InlineData("max_length", 1e12)]
+    public void Validate_WithLengthThatIsNotAWholeNumber_ReturnsError(string type, double value)
+    {
+        Schema(Rule(type, value)).Validate()
+            .Should().ContainSingle()
+            .Which.Should().Be($"Field 'code' has an invalid {type} rule: value must be a non-negative whole number");
+    }
+
+    [Fact]
+    public void Validate_WithNonNumericBound_ReturnsError()
+    {
+        Schema(Rule("min", "ten")).Validate()
+            .Should().ContainSingle()
+            .Which.Should().Be("Field 'code' has an invalid min rule: value must be a number");
+    }
+
+    [Fact]
+    public void Validate_WithInvalidPattern_ReturnsError()
+    {
+        Schema(Rule("pattern", "[0-9")).Validate()
+            .Should().ContainSingle()
+            .Which.Should().StartWith("Field 'code' has an invalid pattern rule:");
+    }
+
+    [Fact]
+    public void Validate_WithRequiredIfOnUnknownField_ReturnsError()
+    {
+        var rule = new DynamicFormValidationRule
+        {
+            Type = "required_if",
+            Condition = new DynamicFormCondition { Field = "missing", Operator = "equals", Value = "yes" }
+        };
+
+        Schema(rule).Validate()
+            .Should().ContainSingle()
+            .Which.Should().Be("Field 'code' has an invalid required_if rule: condition references unknown field 'missing'");
+    }
+
+    [Fact]
+    public void Validate_WithUnsupportedRuleType_ReturnsError()
+    {
+        Schema(Rule("luhn", 1)).Validate()
+            .Should().ContainSingle()
+            .Which.Should().Be("Field 'code' has an invalid luhn rule: unsupported rule type");
+    }
+
+    [Fact]
+    public void Validate_WithShowIfOnAnotherField_ReturnsNoErrors()
+    {
+        var schema = ShowIfSchema(new DynamicFormCondition { Field = "contact", Operator = "equals", Value = "phone" });
+
+        schema.Validate().Should().BeEmpty();
+    }
+
+    [Fact]
+    public void Validate_WithShowIfOnUnknownField_ReturnsError()
+    {
+        var schema = ShowIfSchema(new DynamicFormCondition { Field = "missing", Operator = "not_empty" });
+
+        schema.Validate().Should().ContainSingle()
+            .Which.Should().Be("Field 'phone' has show_if on unknown field 'missing'");
+    }
+
+    [Fact]
+    public void Validate_WithShowIfOnItself_ReturnsError()
+    {
+        var schema = ShowIfSchema(new DynamicFormCondition { Field = "phone", Operator = "empty" });
+
+        schema.Validate().Should().ContainSingle()
+            .Which.Should().Be("Field 'phone' cannot depend on itself in show_if");
+    }
+
+    [Fact]
+    public void Validate_WithUnsupportedShowIfOperator_ReturnsError()
+    {
+        var schema = ShowIfSchema(new DynamicFormCondition { Field = "contact", Operator = "contains", Value = "ph" });
+
+        schema.Validate().Should().ContainSingle()
+            .Which.Should().Be("Field 'phone' has unsupported show_if operator 'contains'");
+    }
+
+    [Fact]
+    public void Validate_WithShowIfOnContentBlock_ReturnsError()
+    {
+        var schema = ShowIfSchema(new DynamicFormCondition { Field = "intro", Operator = "not_empty" });
+        schema.Fields.Insert(0, new DynamicFormField { Name = "intro", Type = "markdown", Content = "Tell us how to reach you" });
+
+        schema.Validate().Should().Contain("Field 'phone' has show_if on unknown field 'intro'");
+    }
+
+    [Theory]
+    [InlineData("given-name")]
+    [InlineData("bday")]
+    [InlineData("section-billing shipping postal-code")]
+    [InlineData("one-time-code")]
+    public void Validate_WithWellFormedAutocompleteHint_ReturnsNoErrors(string autocomplete)
+    {
+        HintSchema(autocomplete: autocomplete).Validate().Should().BeEmpty();
+    }
+
+    [Theory]
+    [InlineData("Given-Name")]
+    [InlineData("postal_code")]
+    [InlineData("name\" onfocus=\"x")]
+    [InlineData(" ")]
+    public void Validate_WithMalformedAutocompleteHint_ReturnsError(string autocomplete)
+    {
+        HintSchema(autocomplete: autocomplete).Validate()
+            .Should().ContainSingle()
+            .Which.Should().Be($"Field 'code' has malformed autocomplete hint '{autocomplete}'");
+    }
+
+    [Theory]
+    [InlineData("numeric")]
+    [InlineData("tel")]
+    [InlineData("Decimal")]
+    public void Validate_WithSupportedInputMode_ReturnsNoErrors(string inputMode)
+    {
+        HintSchema(inputMode: inputMode).Validate().Should().BeEmpty();
+    }
+
+    [Theory]
+    [InlineData("number")]
+    [InlineData("password")]
+    [InlineData("")]
+    public void Validate_WithUnsupportedInputMode_ReturnsError(string inputMode)
+    {
+        HintSchema(inputMode: inputMode).Validate()
+            .Should().ContainSingle()
+            .Which.Should().Be($"Field 'code' has unsupported input mode '{inputMode}'");
+    }
+
+    private static DynamicFormSchema HintSchema(string? autocomplete = null, string? inputMode = null) => new()
+    {
+        Title = "Verify",
+        Fields = new List<DynamicFormField>
+        {
+            new() { Name = "code", Label = "Code", Type = "text", Autocomplete = autocomplete, InputMode = inputMode }
+        }
+    };
+
+    private static DynamicFormSchema ShowIfSchema(DynamicFormCondition condition) => new()
+    {
+        Title = "Contact",
+        Fields = new List<DynamicFormField>
+        {
+            new() { Name = "contact", Label = "Contact by", Type = "select" },
+            new() { Name = "phone", Label = "Phone", Type = "text", ShowIf = condition }
+        }
+    };
+
+    private static DynamicFormSchema Schema(params DynamicFormValidationRule[] rules) => new()
+    {
+        Title = "Verify",
+        Fields = new List<DynamicFormField>
+        {
+            new() { Name = "code", Label = "Code", Type = "text", Validation = rules.ToList() }
+        }
+    };
+
+    private static DynamicFormValidationRule Rule(string type, object value) => new()
+    {
+        Type = type,
+        Value = JsonSerializer.SerializeToElement(value)
+    };
+}
@@ -0,0 +1,35 @@
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class InMemoryPluginAttachmentStoreTests
+{
+    [Fact]
+    public async Task ListAsync_ReturnsOnlyAttachmentsOfTheJourney()
+    {
+        var store = new InMemoryPluginAttachmentStore();
+        var passport = await Store(store, "journey-1", "passport.pdf");
+        await Store(store, "journey-2", "licence.pdf");
+
+        var attachments = await store.ListAsync("journey-1");
+
+        attachments.Should().ContainSingle().Which.Id.Should().Be(passport.Id);
+        (await store.ReadAsync(passport.Id)).Should().Equal(1, 2, 3);
+    }
+
+    [Fact]
+    public async Task ListAsync_AfterClear_ReturnsNothing()
+    {
+        var store = new InMemoryPluginAttachmentStore();
+        await Store(store, "journey-1", "passport.pdf");
+
+        await store.ClearAsync("journey-1");
+
+        (await store.ListAsync("journey-1")).Should().BeEmpty();
+    }
+
+    private static Task<PluginAttachment> Store(InMemoryPluginAttachmentStore store, string journeyId, string fileName) =>
+        store.StoreAsync(journeyId, fileName, "application/pdf", new MemoryStream(new byte[] { 1, 2, 3 }));
+}
@@ -0,0 +1,71 @@
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class InMemoryPluginMessageBusTests
+{
+    [Fact]
+    public async Task GetMessagesAsync_ReturnsMessagesOfTheJourneyByTopic()
+    {
+        var bus = new InMemoryPluginMessageBus();
+        await bus.PublishAsync("journey-1", new PluginMessage { Topic = "risk.score", Payload = 70 });
+        await bus.PublishAsync("journey-1", new PluginMessage { Topic = "device.trusted", Payload = true });
+        await bus.PublishAsync("journey-2", new PluginMessage { Topic = "risk.score", Payload = 10 });
+
+        var messages = await bus.GetMessagesAsync("journey-1", new[] { "risk.score" });
+
+        messages["risk.score"].Select(m => m.Payload).Should().Equal(70);
+    }
+
+    [Fact]
+    public async Task GetMessagesAsync_LeavesOutExpiredMessages()
+    {
+        var bus = new InMemoryPluginMessageBus(TimeSpan.FromMinutes(5));
+        await bus.PublishAsync("journey-1", new PluginMessage { Topic = "risk.score", Payload = 70, PublishedAt = DateTime.UtcNow.AddMinutes(-10) });
+        await bus.PublishAsync("journey-1", new PluginMessage { Topic = "risk.score", Payload = 40 });
+
+        var messages = await bus.GetMessagesAsync("journey-1", new[] { "risk.score" });
+
+        messages["risk.score"].Select(m => m.Payload).Should().Equal(40);
+    }
+
+    [Fact]
+    public async Task PublishAsync_DoesNotExpireOtherJourneys()
+    {
+        var bus = new InMemoryPluginMessageBus(TimeSpan.FromMinutes(5));
+        await bus.PublishAsync("journey-1", new PluginMessage { Topic = "risk.score", Payload = 70 });
+
+        await bus.PublishAsync("journey-2", new PluginMessage { Topic = "risk.score", Payload = 10 });
+
+        var messages = await bus.GetMessagesAsync("journey-1", new[] { "risk.score" });
+        messages["risk.score"].Should().ContainSingle();
+    }
+
+    [Fact]
+    public async Task ClearAsync_RemovesTheJourneysMessages()
+    {
+        var bus = new InMemoryPluginMessageBus();
+        await bus.PublishAsync("journey-1", new PluginMessage { Topic = "risk.score", Payload = 70 });
+
+        await bus.ClearAsync("journey-1");
+
+        var messages = await bus.GetMessagesAsync("journey-1", new[] { "risk.score" });
+        messages.Should().BeEmpty();
+    }
+
+    [Fact]
+    public async Task PublishAllAsync_AttributesMessagesToThePublisher()
+    {
+        IPluginMessageBus bus = new InMemoryPluginMessageBus();
+
+        await bus.PublishAllAsync("journey-1", "risk-plugin", new[]
+        {
+            new PluginMessage { Topic = "risk.score", Payload = 70, Publisher = "someone-else" }
+        });
+
+        var messages = await bus.GetMessagesAsync("journey-1", new[] { "risk.score" });
+        messages["risk.score"].Should().ContainSingle().Which.Publisher.Should().Be("risk-plugin");
+    }
+}
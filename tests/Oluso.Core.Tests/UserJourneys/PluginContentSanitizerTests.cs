@@ -0,0 +1,77 @@
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class PluginContentSanitizerTests
+{
+    [Fact]
+    public void RenderMarkdown_EncodesRawHtml()
+    {
+        var html = PluginContentSanitizer.RenderMarkdown("Hello <script>alert(1)</script>");
+
+        html.Should().Be("<p>Hello &lt;script&gt;alert(1)&lt;/script&gt;</p>");
+    }
+
+    [Fact]
+    public void RenderMarkdown_RendersBlocks()
+    {
+        var html = PluginContentSanitizer.RenderMarkdown(
+            "# Welcome\r\n\r\nline one\nline two\n\n- one\n- **two**\n\n1. first\n2. *second*");
+
+        html.Should().Be(
+            "<h4>Welcome</h4>" +
+            "<p>line one<br />line two</p>" +
+            "<ul><li>one</li><li><strong>two</strong></li></ul>" +
+            "<ol><li>first</li><li><em>second</em></li></ol>");
+    }
+
+    [Fact]
+    public void RenderMarkdown_EncodesCode()
+    {
+        PluginContentSanitizer.RenderMarkdown("Use `<b>` sparingly")
+            .Should().Be("<p>Use <code>&lt;b&gt;</code> sparingly</p>");
+    }
+
+    [Fact]
+    public void RenderMarkdown_RendersAllowedLinks()
+    {
+        var html = PluginContentSanitizer.RenderMarkdown("See [the <docs>](https://example.com/a?b=1&c=2)");
+
+        html.Should().Be(
+            "<p>See <a href=\"https://example.com/a?b=1&amp;c=2\" target=\"_blank\" rel=\"noopener noreferrer\">" +
+            "the &lt;docs&gt;</a></p>");
+    }
+
+    [Theory]
+    [InlineData("[Click](javascript:void0)")]
+    [InlineData("[Click](data:text/html,hi)")]
+    [InlineData("[Click](/relative)")]
+    public void RenderMarkdown_RendersDisallowedLinksAsText(string markdown)
+    {
+        PluginContentSanitizer.RenderMarkdown(markdown).Should().Be("<p>Click</p>");
+    }
+
+    [Fact]
+    public void RenderMarkdown_EncodesQuotesInLinkTargets()
+    {
+        var html = PluginContentSanitizer.RenderMarkdown("[x](https://example.com/\"onmouseover=alert)");
+
+        html.Should().NotContain("\"onmouseover");
+    }
+
+    [Theory]
+    [InlineData("https://example.com", true)]
+    [InlineData("mailto:help@example.com", true)]
+    [InlineData("tel:+447911123456", true)]
+    [InlineData("otpauth://totp/Oluso:ada?secret=JBSWY3DPEHPK3PXP", true)]
+    [InlineData("javascript:alert(1)", false)]
+    [InlineData("ftp://example.com", false)]
+    [InlineData("example.com", false)]
+    [InlineData(null, false)]
+    public void IsAllowedUrl_AllowsOnlyAbsoluteUrlsWithKnownSchemes(string? url, bool allowed)
+    {
+        PluginContentSanitizer.IsAllowedUrl(url).Should().Be(allowed);
+    }
+}
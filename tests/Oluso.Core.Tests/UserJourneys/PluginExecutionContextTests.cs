@@ -0,0 +1,52 @@
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class PluginExecutionContextTests
+{
+    [Fact]
+    public void ForInvocation_CarriesOverContextIncludingSensitiveFields()
+    {
+        var context = new PluginExecutionContext
+        {
+            UserId = "user-1",
+            TenantId = "tenant-1",
+            JourneyId = "journey-1",
+            StepId = "step-1",
+            Input = new Dictionary<string, object> { ["otp"] = "123456" },
+            JourneyData = new Dictionary<string, object> { ["email"] = "ada@example.com" },
+            Config = new Dictionary<string, object> { ["pageSize"] = 50 },
+            ConfigProvenance = new Dictionary<string, string> { ["pageSize"] = "step" },
+            Subscriptions = new[] { "risk.score" },
+            SensitiveInputFields = new[] { "otp" }
+        };
+
+        var page = context.ForInvocation("page1", pageToken: "100");
+
+        page.Should().BeEquivalentTo(context, options => options
+            .Excluding(c => c.PageToken)
+            .Excluding(c => c.IdempotencyKey));
+        page.PageToken.Should().Be("100");
+        page.SensitiveInputFields.Should().Equal("otp");
+    }
+
+    [Fact]
+    public void ForInvocation_ScopesIdempotencyKeyToInvocation()
+    {
+        var context = new PluginExecutionContext
+        {
+            IdempotencyKey = PluginExecutionContext.CreateIdempotencyKey("journey-1", "step-1", 0)
+        };
+
+        context.ForInvocation("page0").IdempotencyKey.Should().Be("journey-1:step-1:0:page0");
+        context.ForInvocation("batch3", checkpoint: "{}").IdempotencyKey.Should().Be("journey-1:step-1:0:batch3");
+    }
+
+    [Fact]
+    public void ForInvocation_WithoutIdempotencyKey_StaysUnkeyed()
+    {
+        new PluginExecutionContext().ForInvocation("page0").IdempotencyKey.Should().BeNull();
+    }
+}
@@ -0,0 +1,65 @@
+using System.Text.Json;
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class PluginFieldOptionsSourceTests
+{
+    [Fact]
+    public void Find_OnTheStepThatShowedTheForm_ReturnsSource()
+    {
+        var source = PluginFieldOptionsSource.Find(CreateState("profile", JourneyStatus.InProgress), "department");
+
+        source.Should().NotBeNull();
+        source!.FunctionName.Should().Be("department_options");
+    }
+
+    [Fact]
+    public void Find_AfterJourneyMovedToAnotherStep_ReturnsNull()
+    {
+        PluginFieldOptionsSource.Find(CreateState("consent", JourneyStatus.InProgress), "department")
+            .Should().BeNull();
+    }
+
+    [Theory]
+    [InlineData(JourneyStatus.Completed)]
+    [InlineData(JourneyStatus.Failed)]
+    [InlineData(JourneyStatus.Cancelled)]
+    public void Find_WhenJourneyIsNotInProgress_ReturnsNull(JourneyStatus status)
+    {
+        PluginFieldOptionsSource.Find(CreateState("profile", status), "department")
+            .Should().BeNull();
+    }
+
+    [Fact]
+    public void Find_ForFieldWithoutSource_ReturnsNull()
+    {
+        PluginFieldOptionsSource.Find(CreateState("profile", JourneyStatus.InProgress), "display_name")
+            .Should().BeNull();
+    }
+
+    private static JourneyState CreateState(string currentStepId, JourneyStatus status) => new()
+    {
+        Id = "journey-1",
+        TenantId = "default",
+        ClientId = "test-client",
+        PolicyId = "onboarding",
+        CurrentStepId = currentStepId,
+        Status = status,
+        // As read back from a distributed state store
+        Data = new Dictionary<string, object>
+        {
+            [PluginFieldOptionsSource.JourneyDataKey] = JsonSerializer.SerializeToElement(new Dictionary<string, PluginFieldOptionsSource>
+            {
+                ["department"] = new()
+                {
+                    PluginName = "hello-plugin",
+                    FunctionName = "department_options",
+                    StepId = "profile"
+                }
+            })
+        }
+    };
+}
@@ -0,0 +1,100 @@
+using System.Text.Json;
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class PluginFileFieldTests
+{
+    private const long StoreMaxSize = 10 * 1024 * 1024;
+
+    [Theory]
+    [InlineData("passport.pdf", "application/pdf", true)]
+    [InlineData("photo.jpg", "image/jpeg", true)]
+    [InlineData("scan.HEIC", "application/octet-stream", true)]
+    [InlineData("notes.txt", "text/plain", false)]
+    [InlineData("payload.exe", "application/x-msdownload", false)]
+    public void Accepts_MatchesContentTypesWildcardsAndExtensions(string fileName, string contentType, bool expected)
+    {
+        var field = new PluginFileField { Label = "Document", Accept = "application/pdf, image/*, .heic" };
+
+        field.Accepts(fileName, contentType).Should().Be(expected);
+    }
+
+    [Fact]
+    public void Accepts_WithoutAccept_AcceptsAnyFile()
+    {
+        var field = new PluginFileField { Label = "Document" };
+
+        field.Accepts("notes.txt", "text/plain").Should().BeTrue();
+    }
+
+    [Fact]
+    public void Validate_LargerThanFieldMaxSize_ReturnsError()
+    {
+        var field = new PluginFileField { Label = "Document", MaxSize = 5 * 1024 * 1024 };
+
+        field.Validate("passport.pdf", "application/pdf", 6 * 1024 * 1024, StoreMaxSize)
+            .Should().Be("Document must be 5 MB or smaller");
+    }
+
+    [Fact]
+    public void Validate_FieldMaxSizeAboveStoreLimit_UsesStoreLimit()
+    {
+        var field = new PluginFileField { Label = "Document", MaxSize = 50 * 1024 * 1024 };
+
+        field.Validate("passport.pdf", "application/pdf", 20 * 1024 * 1024, StoreMaxSize)
+            .Should().Be("Document must be 10 MB or smaller");
+    }
+
+    [Fact]
+    public void Validate_WithUnacceptedType_ReturnsError()
+    {
+        var field = new PluginFileField { Label = "Document", Accept = "application/pdf" };
+
+        field.Validate("notes.txt", "text/plain", 100, StoreMaxSize)
+            .Should().Be("Document must be one of these file types: application/pdf");
+    }
+
+    [Fact]
+    public void Validate_WithAcceptedFile_ReturnsNull()
+    {
+        var field = new PluginFileField { Label = "Document", Accept = "application/pdf", MaxSize = 5 * 1024 * 1024 };
+
+        field.Validate("passport.pdf", "application/pdf", 1024, StoreMaxSize).Should().BeNull();
+    }
+
+    [Fact]
+    public void FromJourneyData_ReadsFieldsFromDeserializedJson()
+    {
+        var fields = new Dictionary<string, PluginFileField>
+        {
+            ["document"] = new() { Label = "Document", Accept = "application/pdf", MaxSize = 1024 }
+        };
+        var journeyData = new Dictionary<string, object>
+        {
+            [PluginFileField.JourneyDataKey] = JsonSerializer.SerializeToElement(fields)
+        };
+
+        var read = PluginFileField.FromJourneyData(journeyData);
+
+        read.Should().ContainKey("document");
+        read["document"].Accept.Should().Be("application/pdf");
+        read["document"].MaxSize.Should().Be(1024);
+    }
+
+    [Fact]
+    public void SchemaValidate_WithMaxSizeOnNonFileField_ReturnsError()
+    {
+        var schema = new DynamicFormSchema
+        {
+            Fields = new List<DynamicFormField>
+            {
+                new() { Name = "nickname", Label = "Nickname", Type = "text", MaxSize = 1024 }
+            }
+        };
+
+        schema.Validate().Should().Contain("Field 'nickname' has a max size but is not a file field");
+    }
+}
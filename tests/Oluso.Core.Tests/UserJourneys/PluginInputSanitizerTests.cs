@@ -0,0 +1,69 @@
+using System.Text.Json;
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class PluginInputSanitizerTests
+{
+    [Fact]
+    public void Sanitize_RemovesWellKnownSecrets()
+    {
+        var input = new Dictionary<string, object> { ["email"] = "ada@example.com", ["Password"] = "hunter2", ["otp"] = "123456" };
+
+        var sanitized = PluginInputSanitizer.Sanitize(input);
+
+        sanitized.Should().ContainKey("email").And.NotContainKeys("Password", "otp");
+    }
+
+    [Fact]
+    public void Sanitize_KeepsAllowedMaskedFields()
+    {
+        var input = new Dictionary<string, object> { ["otp"] = "123456", ["password"] = "hunter2" };
+
+        var sanitized = PluginInputSanitizer.Sanitize(input, new[] { "otp" });
+
+        sanitized.Should().ContainKey("otp").And.NotContainKey("password");
+    }
+
+    [Fact]
+    public void SanitizeExcluding_RemovesMaskedFieldsWithOrdinaryNames()
+    {
+        var input = new Dictionary<string, object> { ["tax_id"] = "123-45-6789", ["display_name"] = "Ada" };
+
+        var sanitized = PluginInputSanitizer.SanitizeExcluding(input, new[] { "tax_id" });
+
+        sanitized.Should().ContainKey("display_name").And.NotContainKey("tax_id");
+    }
+
+    [Fact]
+    public void GetSensitiveFields_ReadsInMemoryAndDeserializedJourneyData()
+    {
+        var recorded = new Dictionary<string, List<string>>
+        {
+            ["identity"] = new() { "tax_id" },
+            ["verify"] = new() { "otp" }
+        };
+        var inMemory = new Dictionary<string, object> { [DynamicFormSchema.SensitiveFieldsDataKey] = recorded };
+        var deserialized = new Dictionary<string, object>
+        {
+            [DynamicFormSchema.SensitiveFieldsDataKey] = JsonSerializer.SerializeToElement(recorded)
+        };
+
+        DynamicFormSchema.GetSensitiveFields(inMemory).Should().BeEquivalentTo("tax_id", "otp");
+        DynamicFormSchema.GetSensitiveFields(deserialized).Should().Contain("TAX_ID");
+        DynamicFormSchema.GetSensitiveFields(deserialized, "verify").Should().BeEquivalentTo("otp");
+        DynamicFormSchema.GetSensitiveFields(inMemory, "profile").Should().BeEmpty();
+    }
+
+    [Theory]
+    [InlineData("password", true)]
+    [InlineData("otp", true)]
+    [InlineData("masked_text", true)]
+    [InlineData("text", false)]
+    public void IsSensitive_DependsOnFieldType(string type, bool expected)
+    {
+        new DynamicFormField { Name = "field", Type = type }.IsSensitive.Should().Be(expected);
+    }
+}
@@ -0,0 +1,177 @@
+using System.Text.Json;
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Xunit;
+
+namespace Oluso.Core.Tests.UserJourneys;
+
+public class PluginWizardStateTests
+{
+    private static readonly IReadOnlyCollection<string> NoSensitiveFields = Array.Empty<string>();
+
+    [Fact]
+    public void Submit_RecordsPageAndAnswers()
+    {
+        var state = new PluginWizardState { Page = "account" };
+
+        state.Submit(new Dictionary<string, object>
+        {
+            ["display_name"] = "Ada",
+            ["__submitted"] = "true"
+        }, NoSensitiveFields);
+
+        state.Submitted.Should().BeTrue();
+        state.Back.Should().BeFalse();
+        state.History.Should().Equal("account");
+        state.Values.Should().ContainKey("display_name").And.NotContainKey("__submitted");
+    }
+
+    [Fact]
+    public void Submit_DoesNotKeepMaskedOrSensitiveFields()
+    {
+        var state = new PluginWizardState { Page = "account" };
+
+        state.Submit(new Dictionary<string, object>
+        {
+            ["email"] = "ada@example.com",
+            ["password"] = "hunter2",
+            ["national_id"] = "AB123456C"
+        }, new[] { "national_id" });
+
+        state.Values.Keys.Should().BeEquivalentTo("email");
+    }
+
+    [Fact]
+    public void Submit_WithBackRequest_KeepsHistoryAndAnswers()
+    {
+        var state = new PluginWizardState { Page = "profile", History = { "account" } };
+
+        state.Submit(new Dictionary<string, object>
+        {
+            [DynamicFormWizard.BackInputName] = "true",
+            ["country"] = "GB"
+        }, NoSensitiveFields);
+
+        state.Back.Should().BeTrue();
+        state.History.Should().Equal("account");
+        state.Values.Should().BeEmpty();
+    }
+
+    [Fact]
+    public void Submit_WithBackRequestOnFirstPage_IsASubmission()
+    {
+        var state = new PluginWizardState { Page = "account" };
+
+        state.Submit(new Dictionary<string, object> { [DynamicFormWizard.BackInputName] = "true" }, NoSensitiveFields);
+
+        state.Back.Should().BeFalse();
+        state.History.Should().Equal("account");
+    }
+
+    [Fact]
+    public void Submit_WithBackRequest_WhenBackDisabled_IsASubmission()
+    {
+        var state = new PluginWizardState
+        {
+            Page = "review",
+            History = { "account" },
+            BackNavigation = DynamicFormWizard.BackDisabled
+        };
+
+        state.Submit(new Dictionary<string, object> { [DynamicFormWizard.BackInputName] = "true" }, NoSensitiveFields);
+
+        state.Back.Should().BeFalse();
+        state.History.Should().Equal("account", "review");
+    }
+
+    [Fact]
+    public void Show_PageAlreadySubmitted_DropsItAndLaterPagesFromHistory()
+    {
+        var state = new PluginWizardState
+        {
+            Page = "review",
+            History = { "account", "profile", "review" },
+            Submitted = true
+        };
+
+        state.Show(Wizard("profile", DynamicFormWizard.BackDisabled));
+
+        state.Page.Should().Be("profile");
+        state.History.Should().Equal("account");
+        state.BackNavigation.Should().Be(DynamicFormWizard.BackDisabled);
+        state.Submitted.Should().BeFalse();
+    }
+
+    [Fact]
+    public void FromJourneyData_ReadsSerializedState()
+    {
+        var states = new Dictionary<string, PluginWizardState>
+        {
+            ["step"] = new() { Page = "profile", History = { "account" }, Values = { ["display_name"] = "Ada" } }
+        };
+        var journeyData = new Dictionary<string, object>
+        {
+            [PluginWizardState.JourneyDataKey] = JsonSerializer.SerializeToElement(states)
+        };
+
+        var state = PluginWizardState.FromJourneyData(journeyData)["step"];
+
+        state.Page.Should().Be("profile");
+        state.History.Should().Equal("account");
+        state.Values.Should().ContainKey("display_name");
+    }
+
+    [Fact]
+    public void Serialize_UsesPluginFacingNames()
+    {
+        var json = JsonSerializer.Serialize(new PluginWizardState { Page = "account" });
+
+        json.Should().Contain("\"page\"").And.Contain("\"history\"").And.Contain("\"backNavigation\"");
+    }
+
+    [Theory]
+    [InlineData("account", 0)]
+    [InlineData("profile", 33)]
+    [InlineData("review", 66)]
+    public void GetProgress_DefaultsToShareOfEarlierPages(string currentPage, int expected)
+    {
+        Wizard(currentPage).GetProgress().Should().Be(expected);
+    }
+
+    [Fact]
+    public void Validate_WithMalformedWizard_ReportsEveryProblem()
+    {
+        var wizard = Wizard("missing", "sometimes");
+        wizard.Pages.Add(new DynamicFormWizardPage { Id = "account", Title = "" });
+        wizard.Progress = 120;
+        var schema = new DynamicFormSchema { Wizard = wizard };
+
+        var errors = schema.Validate();
+
+        errors.Should().Contain(e => e.Contains("needs an id and a title"));
+        errors.Should().Contain(e => e.Contains("'account' is used more than once"));
+        errors.Should().Contain(e => e.Contains("'missing' is not one of its pages"));
+        errors.Should().Contain(e => e.Contains("progress 120"));
+        errors.Should().Contain(e => e.Contains("'sometimes' is not supported"));
+    }
+
+    [Fact]
+    public void Validate_WithoutPages_Fails()
+    {
+        var schema = new DynamicFormSchema { Wizard = new DynamicFormWizard { CurrentPage = "account" } };
+
+        schema.Validate().Should().ContainSingle(e => e == "Wizard has no pages");
+    }
+
+    private static DynamicFormWizard Wizard(string currentPage, string backNavigation = DynamicFormWizard.BackAllowed) => new()
+    {
+        Pages =
+        {
+            new DynamicFormWizardPage { Id = "account", Title = "Account" },
+            new DynamicFormWizardPage { Id = "profile", Title = "Profile" },
+            new DynamicFormWizardPage { Id = "review", Title = "Review" }
+        },
+        CurrentPage = currentPage,
+        BackNavigation = backNavigation
+    };
+}
@@ -0,0 +1,113 @@
+using System.Text.Json;
+using Microsoft.Extensions.DependencyInjection;
+using Microsoft.Extensions.Logging.Abstractions;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.UserJourneys.Plugins;
+using Oluso.UserJourneys.Steps;
+
+namespace Oluso.Tests.UserJourneys.Fixtures;
+
+/// <summary>
+/// Base class for tests of custom plugin steps and the plugin executor, backed by a mocked managed plugin.
+/// </summary>
+public abstract class PluginStepTestBase
+{
+    protected const string JourneyId = "journey-1";
+
+    protected readonly Mock<IManagedPlugin> PluginMock = new();
+    protected readonly Mock<IManagedPluginRegistry> RegistryMock = new();
+    protected readonly string PluginName;
+    protected readonly string StepId;
+
+    /// <summary>
+    /// Context of the latest plugin call set up with <see cref="SetupPluginResult"/>
+    /// </summary>
+    protected PluginExecutionContext? SeenByPlugin;
+
+    protected PluginStepTestBase(string pluginName, string stepId)
+    {
+        PluginName = pluginName;
+        StepId = stepId;
+
+        PluginMock.Setup(p => p.Name).Returns(pluginName);
+        RegistryMock.Setup(r => r.Get(pluginName)).Returns(PluginMock.Object);
+    }
+
+    /// <summary>
+    /// Services with logging and the plugin registry, plus whatever <paramref name="configure"/> adds
+    /// </summary>
+    protected IServiceProvider CreateServices(Action<IServiceCollection>? configure = null)
+    {
+        var services = new ServiceCollection()
+            .AddLogging()
+            .AddSingleton(RegistryMock.Object);
+
+        configure?.Invoke(services);
+        return services.BuildServiceProvider();
+    }
+
+    /// <summary>
+    /// Executor over the mocked plugin registry, with in-memory stores unless given others
+    /// </summary>
+    protected ExtismPluginExecutor CreateExecutor(
+        PluginExecutorOptions? options = null,
+        IPluginIdempotencyStore? idempotencyStore = null,
+        IPluginAttachmentStore? attachmentStore = null,
+        IPluginJobStore? jobStore = null) =>
+        new(RegistryMock.Object, new NullPluginWatcher(), NullLogger<ExtismPluginExecutor>.Instance,
+            options: options, jobStore: jobStore, idempotencyStore: idempotencyStore, attachmentStore: attachmentStore);
+
+    /// <summary>
+    /// Makes the plugin return <paramref name="result"/> from <paramref name="functionName"/>, or from any function
+    /// </summary>
+    protected void SetupPluginResult(PluginExecutionResult result, string? functionName = null)
+    {
+        PluginMock
+            .Setup(p => p.ExecuteAsync(
+                It.Is<string>(f => functionName == null || f == functionName),
+                It.IsAny<PluginExecutionContext>(),
+                It.IsAny<CancellationToken>()))
+            .Callback<string, PluginExecutionContext, CancellationToken>((_, context, _) => SeenByPlugin = context)
+            .ReturnsAsync(result);
+    }
+
+    /// <summary>
+    /// Context for running the step, with fresh services unless given some
+    /// </summary>
+    protected StepExecutionContext CreateContext(
+        IDictionary<string, object>? journeyData = null,
+        IDictionary<string, object>? input = null,
+        IDictionary<string, object>? settings = null,
+        IServiceProvider? services = null) => new()
+    {
+        JourneyId = JourneyId,
+        StepId = StepId,
+        UserId = "user-1",
+        Configuration = new JourneyStepConfiguration { Id = StepId, Type = "custom_plugin", Settings = settings },
+        PluginName = PluginName,
+        JourneyData = journeyData ?? new Dictionary<string, object>(),
+        Input = input != null ? new JourneyStepInput { StepId = StepId, Values = new Dictionary<string, object>(input) } : null,
+        ServiceProvider = services ?? CreateServices()
+    };
+
+    protected static Task<StepHandlerResult> ExecuteStepAsync(StepExecutionContext context) =>
+        new CustomPluginStepHandler().ExecuteAsync(context);
+
+    /// <summary>
+    /// Input as posted by the form, which always includes the submitted marker
+    /// </summary>
+    protected static Dictionary<string, object> Submitted(IDictionary<string, object> input) =>
+        new(input) { ["__submitted"] = "true" };
+
+    /// <summary>
+    /// Result of a plugin asking for input with the form <paramref name="schema"/> (serialized like plugin output)
+    /// </summary>
+    protected static PluginExecutionResult RequireInput(object schema) => new()
+    {
+        Success = true,
+        Action = PluginAction.RequireInput,
+        Output = JsonSerializer.Deserialize<Dictionary<string, object>>(
+            schema as string ?? JsonSerializer.Serialize(schema))
+    };
+}
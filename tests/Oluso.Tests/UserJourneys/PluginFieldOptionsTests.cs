@@ -0,0 +1,176 @@
+using System.Text.Json;
+using FluentAssertions;
+using Microsoft.AspNetCore.Http;
+using Microsoft.AspNetCore.Mvc;
+using Microsoft.AspNetCore.Mvc.RazorPages;
+using Microsoft.Extensions.DependencyInjection;
+using Microsoft.Extensions.Logging.Abstractions;
+using Microsoft.Extensions.Primitives;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Oluso.UI.Pages.Journey;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for loading select options from plugin options functions: the journey page endpoint and the executor
+/// </summary>
+public class PluginFieldOptionsTests : PluginStepTestBase
+{
+    private readonly Mock<IPluginExecutor> _executorMock = new();
+
+    public PluginFieldOptionsTests() : base("hello-plugin", "profile")
+    {
+    }
+
+    [Fact]
+    public async Task OnPostOptionsAsync_ForFieldWithoutRecordedSource_ReturnsNotFound()
+    {
+        var page = CreatePage(CreateState("profile"));
+
+        var result = await page.OnPostOptionsAsync("display_name");
+
+        result.Should().BeOfType<NotFoundResult>();
+        VerifyOptionsNeverLoaded();
+    }
+
+    [Fact]
+    public async Task OnPostOptionsAsync_AfterJourneyMovedOn_ReturnsNotFound()
+    {
+        var page = CreatePage(CreateState("consent"));
+
+        var result = await page.OnPostOptionsAsync("department");
+
+        result.Should().BeOfType<NotFoundResult>();
+        VerifyOptionsNeverLoaded();
+    }
+
+    [Fact]
+    public async Task OnPostOptionsAsync_PassesFormStateWithoutMaskedFields()
+    {
+        PluginExecutionContext? seen = null;
+        _executorMock
+            .Setup(e => e.GetFieldOptionsAsync("hello-plugin", "department_options", It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Callback<string, string, PluginExecutionContext, CancellationToken>((_, _, context, _) => seen = context)
+            .ReturnsAsync(new[] { new DynamicFormOption { Value = "eng", Label = "Engineering" } });
+
+        var page = CreatePage(CreateState("profile"), new Dictionary<string, StringValues>
+        {
+            ["country"] = "NG",
+            ["__query"] = "eng",
+            ["tax_id"] = "123-45-6789"
+        });
+
+        var result = await page.OnPostOptionsAsync("department");
+
+        JsonSerializer.Serialize(result.Should().BeOfType<JsonResult>().Subject.Value)
+            .Should().Be("[{\"value\":\"eng\",\"label\":\"Engineering\"}]");
+        seen!.StepId.Should().Be("profile");
+        seen.Input.Should().Contain("country", "NG").And.Contain("__query", "eng").And.NotContainKey("tax_id");
+    }
+
+    [Fact]
+    public async Task GetFieldOptionsAsync_ParsesOptionsAndPassesFormState()
+    {
+        SetupPluginResult(new PluginExecutionResult
+        {
+            Success = true,
+            Output = JsonSerializer.Deserialize<Dictionary<string, object>>(
+                "{\"options\":[{\"value\":\"eng\",\"label\":\"Engineering\"},{\"value\":\"ops\",\"label\":\"Operations\",\"disabled\":true}]}")
+        }, "department_options");
+
+        var options = await CreateExecutor().GetFieldOptionsAsync("hello-plugin", "department_options", new PluginExecutionContext
+        {
+            JourneyId = JourneyId,
+            StepId = StepId,
+            Input = new Dictionary<string, object> { ["country"] = "NG", ["__query"] = "" }
+        });
+
+        options.Select(o => o.Value).Should().Equal("eng", "ops");
+        options[1].Label.Should().Be("Operations");
+        options[1].Disabled.Should().BeTrue();
+        SeenByPlugin!.Input.Should().Contain("country", "NG");
+    }
+
+    [Theory]
+    [InlineData("{\"options\":\"eng\"}")]
+    [InlineData("{\"options\":[{\"value\":1,\"label\":\"Engineering\"}]}")]
+    [InlineData("{\"items\":[]}")]
+    public async Task GetFieldOptionsAsync_WithMalformedOptions_ReturnsNoOptions(string output)
+    {
+        SetupPluginResult(new PluginExecutionResult
+        {
+            Success = true,
+            Output = JsonSerializer.Deserialize<Dictionary<string, object>>(output)
+        }, "department_options");
+
+        var options = await CreateExecutor().GetFieldOptionsAsync("hello-plugin", "department_options",
+            new PluginExecutionContext { JourneyId = JourneyId, StepId = StepId });
+
+        options.Should().BeEmpty();
+    }
+
+    [Fact]
+    public async Task GetFieldOptionsAsync_WhenFunctionFails_ReturnsNoOptions()
+    {
+        SetupPluginResult(new PluginExecutionResult { Success = false, Error = "directory unavailable" }, "department_options");
+
+        var options = await CreateExecutor().GetFieldOptionsAsync("hello-plugin", "department_options",
+            new PluginExecutionContext { JourneyId = JourneyId, StepId = StepId });
+
+        options.Should().BeEmpty();
+    }
+
+    private IndexModel CreatePage(JourneyState state, Dictionary<string, StringValues>? form = null)
+    {
+        var stateStoreMock = new Mock<IJourneyStateStore>();
+        stateStoreMock.Setup(s => s.GetAsync(JourneyId, It.IsAny<CancellationToken>())).ReturnsAsync(state);
+
+        var services = CreateServices(s => s.AddSingleton(_executorMock.Object));
+        var httpContext = new DefaultHttpContext { RequestServices = services };
+        httpContext.Request.Form = new FormCollection(form ?? new Dictionary<string, StringValues>());
+
+        return new IndexModel(
+            Mock.Of<IJourneyOrchestrator>(),
+            stateStoreMock.Object,
+            Mock.Of<IJourneyPolicyStore>(),
+            services,
+            NullLogger<IndexModel>.Instance)
+        {
+            JourneyId = JourneyId,
+            PageContext = new PageContext { HttpContext = httpContext }
+        };
+    }
+
+    private void VerifyOptionsNeverLoaded() =>
+        _executorMock.Verify(e => e.GetFieldOptionsAsync(It.IsAny<string>(), It.IsAny<string>(),
+            It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()), Times.Never);
+
+    private static JourneyState CreateState(string currentStepId) => new()
+    {
+        Id = JourneyId,
+        TenantId = "default",
+        ClientId = "test-client",
+        PolicyId = "onboarding",
+        CurrentStepId = currentStepId,
+        Status = JourneyStatus.InProgress,
+        Data = new Dictionary<string, object>
+        {
+            [PluginFieldOptionsSource.JourneyDataKey] = new Dictionary<string, PluginFieldOptionsSource>
+            {
+                ["department"] = new()
+                {
+                    PluginName = "hello-plugin",
+                    FunctionName = "department_options",
+                    StepId = "profile"
+                }
+            },
+            [DynamicFormSchema.SensitiveFieldsDataKey] = new Dictionary<string, List<string>>
+            {
+                ["profile"] = new() { "tax_id" }
+            }
+        }
+    };
+}
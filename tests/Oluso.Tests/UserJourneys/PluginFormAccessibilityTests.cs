@@ -0,0 +1,58 @@
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Oluso.UserJourneys.Steps;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for accessibility metadata and input hints of plugin form fields, as rendered by the custom plugin step
+/// </summary>
+public class PluginFormAccessibilityTests : PluginStepTestBase
+{
+    public PluginFormAccessibilityTests() : base("verify", "verify-step")
+    {
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithAccessibilityMetadata_RendersItOnTheField()
+    {
+        SetupForm(new
+        {
+            name = "code",
+            type = "text",
+            label = "Code",
+            ariaLabel = "One-time code",
+            ariaDescription = "The 6 digits we sent to your phone",
+            autocomplete = "one-time-code",
+            inputMode = "Numeric"
+        });
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        var field = ((DynamicFormViewModel)result.StepResult!.ViewModel!).Fields.Single();
+        field.AriaLabel.Should().Be("One-time code");
+        field.AriaDescription.Should().Be("The 6 digits we sent to your phone");
+        field.Autocomplete.Should().Be("one-time-code");
+        field.InputMode.Should().Be("numeric");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithUnsupportedInputMode_FailsWithInvalidForm()
+    {
+        SetupForm(new { name = "code", type = "text", label = "Code", inputMode = "number" });
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        result.Outcome.Should().Be(StepOutcome.Failed);
+        result.Error.Should().Be("plugin_form_invalid");
+        result.ErrorDescription.Should().Contain("input mode 'number'");
+    }
+
+    private void SetupForm(object field) => SetupPluginResult(RequireInput(new
+    {
+        title = "Verify",
+        fields = new[] { field }
+    }));
+}
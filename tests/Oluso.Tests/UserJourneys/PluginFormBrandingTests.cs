@@ -0,0 +1,83 @@
+using FluentAssertions;
+using Microsoft.Extensions.DependencyInjection;
+using Moq;
+using Oluso.Core.Domain.Interfaces;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Oluso.UserJourneys.Steps;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for clamping plugin form branding to what the tenant allows
+/// </summary>
+public class PluginFormBrandingTests : PluginStepTestBase
+{
+    public PluginFormBrandingTests() : base("partner", "partner-step")
+    {
+        SetupPluginResult(RequireInput(new
+        {
+            title = "Partner sign-up",
+            fields = new[] { new { name = "email", type = "email", label = "Email" } },
+            branding = new { logo = "partner-a", accentColor = "#FF0000", layout = "wide" }
+        }));
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_AppliesBrandingTheTenantAllows()
+    {
+        var viewModel = await RenderAsync(new PluginBrandingSettings
+        {
+            Logos = new Dictionary<string, string> { ["partner-a"] = "https://cdn.example.com/partner-a.png" },
+            AccentColors = new List<string> { "#ff0000" }
+        });
+
+        viewModel.LogoUrl.Should().Be("https://cdn.example.com/partner-a.png");
+        viewModel.AccentColor.Should().Be("#ff0000");
+        viewModel.Layout.Should().Be("wide");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_DropsBrandingTheTenantDoesNotAllow()
+    {
+        var viewModel = await RenderAsync(new PluginBrandingSettings
+        {
+            AccentColors = new List<string> { "#1a73e8" },
+            Layouts = new List<string> { "narrow" }
+        });
+
+        viewModel.LogoUrl.Should().BeNull();
+        viewModel.AccentColor.Should().BeNull();
+        viewModel.Layout.Should().BeNull();
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithoutTenantSettings_AllowsOnlyLayouts()
+    {
+        var viewModel = await RenderAsync(null);
+
+        viewModel.LogoUrl.Should().BeNull();
+        viewModel.AccentColor.Should().BeNull();
+        viewModel.Layout.Should().Be("wide");
+    }
+
+    private async Task<DynamicFormViewModel> RenderAsync(PluginBrandingSettings? allowed)
+    {
+        var services = CreateServices(s =>
+        {
+            if (allowed != null)
+            {
+                var settingsMock = new Mock<ITenantSettingsProvider>();
+                settingsMock
+                    .Setup(p => p.GetSettingsAsync<PluginBrandingSettings>(It.IsAny<CancellationToken>()))
+                    .ReturnsAsync(allowed);
+                s.AddSingleton(settingsMock.Object);
+            }
+        });
+
+        var result = await ExecuteStepAsync(CreateContext(services: services));
+
+        return (DynamicFormViewModel)result.StepResult!.ViewModel!;
+    }
+}
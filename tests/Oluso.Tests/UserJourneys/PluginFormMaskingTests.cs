@@ -0,0 +1,87 @@
+using FluentAssertions;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for masked fields in plugin forms: which values the plugin sees and what is recorded
+/// </summary>
+public class PluginFormMaskingTests : PluginStepTestBase
+{
+    public PluginFormMaskingTests() : base("verify", "verify-step")
+    {
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_ShowingMaskedFields_RecordsThemAsSensitiveForTheStep()
+    {
+        SetupPluginResult(RequireInput(new
+        {
+            title = "Verify",
+            fields = new object[]
+            {
+                new { name = "tax_id", type = "masked_text", label = "Tax ID" },
+                new { name = "code", type = "otp", label = "Code" },
+                new { name = "display_name", type = "text", label = "Display name" }
+            }
+        }));
+
+        var result = await ExecuteStepAsync(CreateContext(RecordedSensitiveFields("identity", "passport_no")));
+
+        DynamicFormSchema.GetSensitiveFields(result.OutputData, StepId)
+            .Should().BeEquivalentTo("tax_id", "code");
+        DynamicFormSchema.GetSensitiveFields(result.OutputData)
+            .Should().BeEquivalentTo("tax_id", "code", "passport_no");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_OnSubmit_PassesMaskedValuesToThePluginAsSensitive()
+    {
+        SetupPluginResult(new PluginExecutionResult { Success = true, Action = PluginAction.Continue });
+
+        await ExecuteStepAsync(CreateContext(
+            RecordedSensitiveFields(StepId, "tax_id"),
+            Submitted(new Dictionary<string, object> { ["tax_id"] = "123-45-6789" })));
+
+        SeenByPlugin!.Input.Should().ContainKey("tax_id");
+        SeenByPlugin.SensitiveInputFields.Should().Contain("tax_id");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_OnSubmit_DoesNotAllowMaskedFieldsOfOtherSteps()
+    {
+        SetupPluginResult(new PluginExecutionResult { Success = true, Action = PluginAction.Continue });
+
+        await ExecuteStepAsync(CreateContext(
+            RecordedSensitiveFields("reset-password", "password"),
+            Submitted(new Dictionary<string, object> { ["password"] = "hunter2", ["display_name"] = "Ada" })));
+
+        SeenByPlugin!.SensitiveInputFields.Should().BeEmpty();
+
+        // The executor applies the allow-list when it sanitizes the input
+        PluginInputSanitizer.Sanitize(SeenByPlugin.Input, SeenByPlugin.SensitiveInputFields)
+            .Should().ContainKey("display_name").And.NotContainKey("password");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_OnSubmit_DoesNotRecordMaskedValuesForCompensation()
+    {
+        PluginMock.Setup(p => p.SupportsCompensation).Returns(true);
+        SetupPluginResult(new PluginExecutionResult { Success = true, Action = PluginAction.Continue });
+
+        var result = await ExecuteStepAsync(CreateContext(
+            RecordedSensitiveFields(StepId, "tax_id"),
+            Submitted(new Dictionary<string, object> { ["tax_id"] = "123-45-6789", ["display_name"] = "Ada" })));
+
+        var entry = PluginCompensationEntry.FromJourneyData(result.OutputData).Should().ContainSingle().Subject;
+        entry.Input.Should().ContainKey("display_name").And.NotContainKey("tax_id");
+    }
+
+    private static Dictionary<string, object> RecordedSensitiveFields(string stepId, params string[] fields) => new()
+    {
+        [DynamicFormSchema.SensitiveFieldsDataKey] = new Dictionary<string, List<string>> { [stepId] = fields.ToList() }
+    };
+}
@@ -0,0 +1,58 @@
+using FluentAssertions;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Oluso.UserJourneys.Steps;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for validation rules in plugin forms, as rendered by the custom plugin step
+/// </summary>
+public class PluginFormValidationTests : PluginStepTestBase
+{
+    public PluginFormValidationTests() : base("verify", "verify-step")
+    {
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithRules_RendersConstraintAttributes()
+    {
+        SetupForm(new object[]
+        {
+            new { type = "pattern", value = "[0-9]{6}", message = "Enter the 6 digits of the code" },
+            new { type = "min_length", value = 6 },
+            new { type = "max_length", value = 6 },
+            new { type = "min", value = 100000 },
+            new { type = "max", value = 999999.5 }
+        });
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        var field = ((DynamicFormViewModel)result.StepResult!.ViewModel!).Fields.Single();
+        field.Pattern.Should().Be("[0-9]{6}");
+        field.MinLength.Should().Be(6);
+        field.MaxLength.Should().Be(6);
+        field.Min.Should().Be("100000");
+        field.Max.Should().Be("999999.5");
+        field.ValidationMessages.Should().Contain("pattern", "Enter the 6 digits of the code");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithFractionalLength_FailsWithInvalidForm()
+    {
+        SetupForm(new object[] { new { type = "min_length", value = 3.5 } });
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        result.Outcome.Should().Be(StepOutcome.Failed);
+        result.Error.Should().Be("plugin_form_invalid");
+        result.ErrorDescription.Should().Contain("min_length");
+    }
+
+    private void SetupForm(object[] rules) => SetupPluginResult(RequireInput(new
+    {
+        title = "Verify",
+        fields = new[] { new { name = "code", type = "text", label = "Code", validation = rules } }
+    }));
+}
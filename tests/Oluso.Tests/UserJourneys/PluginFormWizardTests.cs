@@ -0,0 +1,212 @@
+using System.Text.Json;
+using FluentAssertions;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Oluso.UserJourneys.Steps;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for multi-page plugin forms shown by the custom plugin step
+/// </summary>
+public class PluginFormWizardTests : PluginStepTestBase
+{
+    private JsonElement _wizardSeenByPlugin;
+
+    public PluginFormWizardTests() : base("onboarding", "onboarding-step")
+    {
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithWizardPage_RendersStepperWithBackButton()
+    {
+        SetupWizardResult(RequireInput(WizardPage("profile")));
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        result.Outcome.Should().Be(StepOutcome.RequireInput);
+        var viewModel = result.StepResult!.ViewModel.Should().BeOfType<DynamicFormViewModel>().Subject;
+        viewModel.Wizard.Should().NotBeNull();
+        viewModel.Wizard!.Pages.Should().Equal("Account", "Profile", "Review");
+        viewModel.Wizard.CurrentIndex.Should().Be(1);
+        viewModel.Wizard.Progress.Should().Be(33);
+        viewModel.Wizard.CanGoBack.Should().BeTrue();
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithWizardPage_RecordsPageShown()
+    {
+        SetupWizardResult(RequireInput(WizardPage("profile")));
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        var states = PluginWizardState.FromJourneyData(result.OutputData);
+        states.Should().ContainKey(StepId);
+        states[StepId].Page.Should().Be("profile");
+        states[StepId].Submitted.Should().BeFalse();
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_OnFirstPage_HidesBackButton()
+    {
+        SetupWizardResult(RequireInput(WizardPage("account")));
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        var viewModel = (DynamicFormViewModel)result.StepResult!.ViewModel!;
+        viewModel.Wizard!.CanGoBack.Should().BeFalse();
+        viewModel.Wizard.Progress.Should().Be(0);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithBackNavigationDisabled_HidesBackButton()
+    {
+        SetupWizardResult(RequireInput(WizardPage("review", backNavigation: "disabled", progress: 90)));
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        var viewModel = (DynamicFormViewModel)result.StepResult!.ViewModel!;
+        viewModel.Wizard!.CanGoBack.Should().BeFalse();
+        viewModel.Wizard.Progress.Should().Be(90);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithBackRequest_ReturnsToPreviousPage()
+    {
+        var journeyData = WithWizardState(new PluginWizardState
+        {
+            Page = "profile",
+            History = { "account" },
+            Values = { ["display_name"] = "Ada" }
+        });
+        SetupWizardResult(RequireInput(WizardPage("account")));
+
+        var result = await ExecuteStepAsync(
+            CreateContext(journeyData, Submitted(new Dictionary<string, object> { ["__wizard_back"] = "true" })));
+
+        var seenByPlugin = WizardSeenByPlugin();
+        seenByPlugin.Back.Should().BeTrue();
+        seenByPlugin.History.Should().Equal("account");
+
+        var saved = PluginWizardState.FromJourneyData(result.OutputData)[StepId];
+        saved.Page.Should().Be("account");
+        saved.History.Should().BeEmpty();
+        saved.Back.Should().BeFalse();
+        saved.Values.Should().ContainKey("display_name");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithBackRequest_WhenBackDisabled_TreatsItAsSubmission()
+    {
+        var journeyData = WithWizardState(new PluginWizardState
+        {
+            Page = "review",
+            History = { "account", "profile" },
+            BackNavigation = DynamicFormWizard.BackDisabled
+        });
+        SetupWizardResult(RequireInput(WizardPage("review", backNavigation: "disabled")));
+
+        await ExecuteStepAsync(CreateContext(
+            journeyData,
+            Submitted(new Dictionary<string, object> { ["__wizard_back"] = "true", ["accept_terms"] = "true" })));
+
+        var seenByPlugin = WizardSeenByPlugin();
+        seenByPlugin.Back.Should().BeFalse();
+        seenByPlugin.Submitted.Should().BeTrue();
+        seenByPlugin.History.Should().Equal("account", "profile", "review");
+        seenByPlugin.Values.Should().ContainKey("accept_terms");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WhenPageIsRenderedAgain_DoesNotMoveWizard()
+    {
+        var journeyData = WithWizardState(new PluginWizardState { Page = "profile", History = { "account" } });
+        SetupWizardResult(RequireInput(WizardPage("profile")));
+
+        // A refresh re-renders the current step with input that is not a form submission
+        var result = await ExecuteStepAsync(CreateContext(journeyData, new Dictionary<string, object>()));
+
+        var seenByPlugin = WizardSeenByPlugin();
+        seenByPlugin.Submitted.Should().BeFalse();
+        seenByPlugin.History.Should().Equal("account");
+
+        var saved = PluginWizardState.FromJourneyData(result.OutputData)[StepId];
+        saved.Page.Should().Be("profile");
+        saved.History.Should().Equal("account");
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WhenStepCompletes_ClearsWizardState()
+    {
+        var journeyData = WithWizardState(new PluginWizardState { Page = "review", History = { "account", "profile" } });
+        SetupWizardResult(new PluginExecutionResult { Success = true, Action = PluginAction.Continue });
+
+        var result = await ExecuteStepAsync(
+            CreateContext(journeyData, Submitted(new Dictionary<string, object> { ["accept_terms"] = "true" })));
+
+        result.Outcome.Should().Be(StepOutcome.Continue);
+        PluginWizardState.FromJourneyData(result.OutputData).Should().NotContainKey(StepId);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithUnknownCurrentPage_FailsWithInvalidForm()
+    {
+        SetupWizardResult(RequireInput(WizardPage("missing")));
+
+        var result = await ExecuteStepAsync(CreateContext());
+
+        result.Outcome.Should().Be(StepOutcome.Failed);
+        result.Error.Should().Be("plugin_form_invalid");
+        result.ErrorDescription.Should().Contain("'missing'");
+    }
+
+    /// <summary>
+    /// Like <see cref="PluginStepTestBase.SetupPluginResult"/>, but snapshots the wizard state the plugin was given
+    /// </summary>
+    private void SetupWizardResult(PluginExecutionResult result)
+    {
+        PluginMock
+            .Setup(p => p.ExecuteAsync(It.IsAny<string>(), It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Callback<string, PluginExecutionContext, CancellationToken>((_, context, _) =>
+            {
+                if (context.JourneyData?.TryGetValue(PluginWizardState.JourneyDataKey, out var wizards) == true)
+                    _wizardSeenByPlugin = JsonSerializer.SerializeToElement(wizards);
+            })
+            .ReturnsAsync(result);
+    }
+
+    /// <summary>
+    /// Wizard state as serialized to the plugin (later changes to the in-memory state are not visible)
+    /// </summary>
+    private PluginWizardState WizardSeenByPlugin() =>
+        PluginWizardState.FromJourneyData(new Dictionary<string, object>
+        {
+            [PluginWizardState.JourneyDataKey] = _wizardSeenByPlugin
+        })[StepId];
+
+    private Dictionary<string, object> WithWizardState(PluginWizardState state) => new()
+    {
+        [PluginWizardState.JourneyDataKey] = new Dictionary<string, PluginWizardState> { [StepId] = state }
+    };
+
+    private static object WizardPage(string currentPage, string backNavigation = "allowed", int? progress = null) =>
+        new
+        {
+            title = "Onboarding",
+            fields = new[] { new { name = "display_name", type = "text", label = "Display name" } },
+            wizard = new
+            {
+                pages = new[]
+                {
+                    new { id = "account", title = "Account" },
+                    new { id = "profile", title = "Profile" },
+                    new { id = "review", title = "Review" }
+                },
+                currentPage,
+                progress,
+                backNavigation
+            }
+        };
+}
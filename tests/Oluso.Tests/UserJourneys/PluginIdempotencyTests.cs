@@ -0,0 +1,179 @@
+using FluentAssertions;
+using Microsoft.Extensions.DependencyInjection;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for the executor replaying completed results of retried invocations
+/// </summary>
+public class PluginIdempotencyTests : PluginStepTestBase
+{
+    private readonly Queue<PluginExecutionResult> _results = new();
+    private Task? _blockInvocations;
+    private int _invocations;
+
+    public PluginIdempotencyTests() : base("accounts", "provision")
+    {
+        PluginMock
+            .Setup(p => p.ExecuteAsync(It.IsAny<string>(), It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Returns(async () =>
+            {
+                var invocation = Interlocked.Increment(ref _invocations);
+                if (_blockInvocations != null)
+                {
+                    await _blockInvocations;
+                }
+                return _results.Count > 0 ? _results.Dequeue() : Completed($"acct-{invocation}");
+            });
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithSameKey_ReplaysCompletedResult()
+    {
+        var executor = CreateExecutor();
+
+        var first = await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        var retry = await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+
+        _invocations.Should().Be(1);
+        retry.Output!["account_id"].Should().Be("acct-1");
+        retry.Should().BeSameAs(first);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithSameKeyWhileRunning_AwaitsTheFirstInvocation()
+    {
+        var release = new TaskCompletionSource();
+        _blockInvocations = release.Task;
+        var executor = CreateExecutor();
+
+        var first = executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        var retry = executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        release.SetResult();
+
+        var results = await Task.WhenAll(first, retry);
+
+        _invocations.Should().Be(1);
+        results[1].Should().BeSameAs(results[0]);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithDifferentKeyOrFunction_RunsAgain()
+    {
+        var executor = CreateExecutor();
+
+        await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:1"));
+        await executor.ExecuteAsync("accounts", "notify_admin", Context("journey-1:provision:1"));
+
+        _invocations.Should().Be(3);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithoutKey_AlwaysRuns()
+    {
+        var executor = CreateExecutor();
+
+        await executor.ExecuteAsync("accounts", "provision_account", Context(null));
+        await executor.ExecuteAsync("accounts", "provision_account", Context(null));
+
+        _invocations.Should().Be(2);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_AfterFailure_RunsAgain()
+    {
+        _results.Enqueue(new PluginExecutionResult { Success = false, Error = "directory unavailable" });
+        var executor = CreateExecutor();
+
+        var failed = await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        var retry = await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+
+        failed.Success.Should().BeFalse();
+        retry.Success.Should().BeTrue();
+        _invocations.Should().Be(2);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_AfterInputRequest_RunsAgain()
+    {
+        _results.Enqueue(new PluginExecutionResult { Success = true, Action = PluginAction.RequireInput });
+        var executor = CreateExecutor();
+
+        await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        var submitted = await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+
+        submitted.Action.Should().Be(PluginAction.Continue);
+        _invocations.Should().Be(2);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_AfterWindow_RunsAgain()
+    {
+        var executor = CreateExecutor(new PluginExecutorOptions { IdempotencyWindow = TimeSpan.Zero });
+
+        await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        await executor.ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+
+        _invocations.Should().Be(2);
+    }
+
+    [Fact]
+    public async Task ExecuteAsync_WithSharedStore_ReplaysAcrossExecutors()
+    {
+        using var store = new InMemoryPluginIdempotencyStore();
+
+        await CreateExecutor(idempotencyStore: store)
+            .ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+        var retry = await CreateExecutor(idempotencyStore: store)
+            .ExecuteAsync("accounts", "provision_account", Context("journey-1:provision:0"));
+
+        _invocations.Should().Be(1);
+        retry.Output!["account_id"].Should().Be("acct-1");
+    }
+
+    [Fact]
+    public async Task ManagedPluginStep_WhenRetried_ReplaysCompletedResult()
+    {
+        var services = CreateServices(s => s.AddSingleton<IPluginExecutor>(CreateExecutor()));
+        var settings = new Dictionary<string, object> { ["entryPoint"] = "provision_account" };
+
+        // The retry sees the journey data of before the first attempt, as after a lost response
+        var first = await ExecuteStepAsync(CreateContext(settings: settings, services: services));
+        var retry = await ExecuteStepAsync(CreateContext(settings: settings, services: services));
+
+        _invocations.Should().Be(1);
+        retry.OutputData!["account_id"].Should().Be(first.OutputData!["account_id"]);
+    }
+
+    [Fact]
+    public async Task InMemoryStore_RemoveExpired_DropsOnlyExpiredResults()
+    {
+        using var store = new InMemoryPluginIdempotencyStore();
+        await store.SetAsync("expired", Completed("acct-1"), TimeSpan.Zero);
+        await store.SetAsync("live", Completed("acct-2"), TimeSpan.FromMinutes(5));
+
+        store.RemoveExpired();
+
+        store.Count.Should().Be(1);
+        (await store.GetAsync("live")).Should().NotBeNull();
+    }
+
+    private static PluginExecutionContext Context(string? idempotencyKey) => new()
+    {
+        JourneyId = JourneyId,
+        StepId = "provision",
+        IdempotencyKey = idempotencyKey,
+        Input = new Dictionary<string, object> { ["email"] = "ada@example.com" }
+    };
+
+    private static PluginExecutionResult Completed(string accountId) => new()
+    {
+        Success = true,
+        Output = new Dictionary<string, object> { ["account_id"] = accountId }
+    };
+}
@@ -0,0 +1,290 @@
+using FluentAssertions;
+using Microsoft.Extensions.Caching.Distributed;
+using Microsoft.Extensions.Caching.Memory;
+using Microsoft.Extensions.DependencyInjection;
+using Microsoft.Extensions.Logging.Abstractions;
+using Microsoft.Extensions.Options;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Oluso.UserJourneys.Plugins;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for long-running plugin jobs: queuing from a step, the durable job store and the job processor
+/// </summary>
+public class PluginJobTests : PluginStepTestBase
+{
+    private readonly IDistributedCache _cache =
+        new MemoryDistributedCache(Options.Create(new MemoryDistributedCacheOptions()));
+
+    private static readonly IDictionary<string, object> JobSettings = new Dictionary<string, object>
+    {
+        ["entryPoint"] = "migrate_users",
+        ["job"] = true
+    };
+
+    private static readonly IDictionary<string, object> JobInput = new Dictionary<string, object>
+    {
+        ["batch_size"] = "500",
+        ["password"] = "hunter2"
+    };
+
+    public PluginJobTests() : base("hello-plugin", "migrate")
+    {
+    }
+
+    [Fact]
+    public async Task DistributedCacheStore_RoundTripsJobState()
+    {
+        var store = CreateStore();
+
+        await store.SaveAsync(Job("job-1"));
+
+        var job = await store.GetAsync("job-1");
+        job.Should().NotBeNull();
+        job!.PluginName.Should().Be("hello-plugin");
+        job.JourneyId.Should().Be("journey-1");
+        job.Input.Should().ContainKey("batch_size");
+        job.Checkpoint.Should().Be("{\"offset\":2000}");
+    }
+
+    [Fact]
+    public async Task DistributedCacheStore_ListsOnlyRunningJobs()
+    {
+        var store = CreateStore();
+        await store.SaveAsync(Job("job-1"));
+        await store.SaveAsync(Job("job-2"));
+
+        var finished = Job("job-2");
+        finished.Status = PluginJobStatus.Completed;
+        await store.SaveAsync(finished);
+
+        var running = await store.GetRunningAsync();
+
+        running.Select(j => j.JobId).Should().Equal("job-1");
+    }
+
+    [Fact]
+    public async Task DistributedCacheStore_RunningJobsSurviveANewStoreInstance()
+    {
+        await CreateStore().SaveAsync(Job("job-1"));
+
+        // A new instance over the same cache stands in for a restarted process
+        var running = await CreateStore().GetRunningAsync();
+
+        running.Should().ContainSingle(j => j.JobId == "job-1");
+    }
+
+    [Fact]
+    public async Task DistributedCacheStore_ClaimIsExclusiveUntilReleased()
+    {
+        var store = CreateStore();
+
+        (await store.TryClaimAsync("job-1", "instance-a", TimeSpan.FromMinutes(1))).Should().BeTrue();
+        (await store.TryClaimAsync("job-1", "instance-b", TimeSpan.FromMinutes(1))).Should().BeFalse();
+        (await store.TryClaimAsync("job-1", "instance-a", TimeSpan.FromMinutes(1))).Should().BeTrue();
+
+        await store.ReleaseAsync("job-1", "instance-b");
+        (await store.TryClaimAsync("job-1", "instance-b", TimeSpan.FromMinutes(1))).Should().BeFalse();
+
+        await store.ReleaseAsync("job-1", "instance-a");
+        (await store.TryClaimAsync("job-1", "instance-b", TimeSpan.FromMinutes(1))).Should().BeTrue();
+    }
+
+    [Fact]
+    public async Task InMemoryStore_ExpiredClaimCanBeTakenOver()
+    {
+        var store = new InMemoryPluginJobStore();
+
+        (await store.TryClaimAsync("job-1", "instance-a", TimeSpan.Zero)).Should().BeTrue();
+
+        (await store.TryClaimAsync("job-1", "instance-b", TimeSpan.FromMinutes(1))).Should().BeTrue();
+        (await store.TryClaimAsync("job-1", "instance-a", TimeSpan.FromMinutes(1))).Should().BeFalse();
+    }
+
+    [Fact]
+    public async Task Processor_DoesNotStartAJobClaimedByAnotherInstance()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        await jobStore.SaveAsync(Job("job-1"));
+        await jobStore.TryClaimAsync("job-1", "other-instance", TimeSpan.FromMinutes(1));
+
+        var executorMock = new Mock<IPluginExecutor>();
+
+        var started = await CreateProcessor(executorMock.Object, jobStore).StartPendingJobsAsync();
+
+        started.Should().Be(0);
+        executorMock.Verify(e => e.RunJobAsync(It.IsAny<string>(), It.IsAny<string>(), It.IsAny<string>(),
+            It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()), Times.Never);
+    }
+
+    [Fact]
+    public async Task Processor_ReleasesClaimWhenJobEnds()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        await jobStore.SaveAsync(Job("job-1"));
+
+        var finished = new TaskCompletionSource();
+        var executorMock = new Mock<IPluginExecutor>();
+        executorMock
+            .Setup(e => e.RunJobAsync(It.IsAny<string>(), It.IsAny<string>(), "job-1", It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Callback(() => finished.TrySetResult())
+            .ReturnsAsync((PluginJobState)null!);
+
+        await CreateProcessor(executorMock.Object, jobStore).StartPendingJobsAsync();
+        await finished.Task.WaitAsync(TimeSpan.FromSeconds(5));
+
+        // The claim is released just after the job returns
+        var claimed = false;
+        for (var attempt = 0; attempt < 50 && !claimed; attempt++)
+        {
+            claimed = await jobStore.TryClaimAsync("job-1", "other-instance", TimeSpan.FromMinutes(1));
+            if (!claimed)
+            {
+                await Task.Delay(20);
+            }
+        }
+
+        claimed.Should().BeTrue();
+    }
+
+    [Fact]
+    public async Task RunJobAsync_WhenCancelled_KeepsJobRunningAtItsCheckpoint()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        await jobStore.SaveAsync(Job("job-1"));
+
+        using var shutdown = new CancellationTokenSource();
+        PluginMock
+            .Setup(p => p.ExecuteAsync("migrate_users", It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Callback(() => shutdown.Cancel())
+            .ReturnsAsync(new PluginExecutionResult { Success = false, Error = "interrupted" });
+
+        var act = () => CreateExecutor(jobStore: jobStore).RunJobAsync("hello-plugin", "migrate_users", "job-1",
+            new PluginExecutionContext { JourneyId = JourneyId }, shutdown.Token);
+
+        await act.Should().ThrowAsync<OperationCanceledException>();
+        var job = await jobStore.GetAsync("job-1");
+        job!.Status.Should().Be(PluginJobStatus.Running);
+        job.Checkpoint.Should().Be("{\"offset\":2000}");
+        job.Error.Should().BeNull();
+    }
+
+    [Fact]
+    public async Task Processor_ResumesRunningJobWithItsContext()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        await jobStore.SaveAsync(Job("job-1"));
+
+        var invoked = new TaskCompletionSource<PluginExecutionContext>();
+        var executorMock = new Mock<IPluginExecutor>();
+        executorMock
+            .Setup(e => e.RunJobAsync("hello-plugin", "migrate_users", "job-1", It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Callback<string, string, string, PluginExecutionContext, CancellationToken>((_, _, _, context, _) => invoked.TrySetResult(context))
+            .ReturnsAsync((PluginJobState)null!);
+
+        var started = await CreateProcessor(executorMock.Object, jobStore).StartPendingJobsAsync();
+
+        started.Should().Be(1);
+        var context = await invoked.Task.WaitAsync(TimeSpan.FromSeconds(5));
+        context.UserId.Should().Be("user-1");
+        context.JourneyId.Should().Be("journey-1");
+        context.IdempotencyKey.Should().Be("job-1");
+        context.Input.Should().ContainKey("batch_size");
+        context.Config.Should().ContainKey("batch_size");
+    }
+
+    [Fact]
+    public async Task Processor_DoesNotStartAJobItIsAlreadyRunning()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        await jobStore.SaveAsync(Job("job-1"));
+
+        var executorMock = new Mock<IPluginExecutor>();
+        executorMock
+            .Setup(e => e.RunJobAsync(It.IsAny<string>(), It.IsAny<string>(), It.IsAny<string>(), It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Returns(new TaskCompletionSource<PluginJobState>().Task);
+
+        var processor = CreateProcessor(executorMock.Object, jobStore);
+
+        (await processor.StartPendingJobsAsync()).Should().Be(1);
+        (await processor.StartPendingJobsAsync()).Should().Be(0);
+    }
+
+    [Fact]
+    public async Task Processor_LimitsConcurrentJobs()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        await jobStore.SaveAsync(Job("job-1"));
+        await jobStore.SaveAsync(Job("job-2"));
+
+        var executorMock = new Mock<IPluginExecutor>();
+        executorMock
+            .Setup(e => e.RunJobAsync(It.IsAny<string>(), It.IsAny<string>(), It.IsAny<string>(), It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .Returns(new TaskCompletionSource<PluginJobState>().Task);
+
+        var processor = CreateProcessor(executorMock.Object, jobStore, new PluginExecutorOptions { MaxConcurrentJobs = 1 });
+
+        (await processor.StartPendingJobsAsync()).Should().Be(1);
+    }
+
+    [Fact]
+    public async Task JobStep_QueuesJobAndContinues()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        var services = CreateServices(s => s.AddSingleton<IPluginJobStore>(jobStore));
+
+        var result = await ExecuteStepAsync(CreateContext(input: JobInput, settings: JobSettings, services: services));
+
+        result.Outcome.Should().Be(StepOutcome.Continue);
+        result.OutputData.Should().ContainKey(PluginJobState.JourneyDataKey);
+
+        var jobId = result.OutputData![PluginJobState.JourneyDataKey].ToString()!;
+        var job = await jobStore.GetAsync(jobId);
+        job.Should().NotBeNull();
+        job!.FunctionName.Should().Be("migrate_users");
+        job.Status.Should().Be(PluginJobStatus.Running);
+        job.Input.Should().ContainKey("batch_size").And.NotContainKey("password");
+    }
+
+    [Fact]
+    public async Task JobStep_WhenRetried_DoesNotQueueAnotherJob()
+    {
+        var jobStore = new InMemoryPluginJobStore();
+        var services = CreateServices(s => s.AddSingleton<IPluginJobStore>(jobStore));
+
+        var first = await ExecuteStepAsync(CreateContext(input: JobInput, settings: JobSettings, services: services));
+        var retry = await ExecuteStepAsync(CreateContext(input: JobInput, settings: JobSettings, services: services));
+
+        retry.OutputData![PluginJobState.JourneyDataKey].Should().Be(first.OutputData![PluginJobState.JourneyDataKey]);
+        (await jobStore.GetRunningAsync()).Should().ContainSingle();
+    }
+
+    private DistributedCachePluginJobStore CreateStore() =>
+        new(_cache, NullLogger<DistributedCachePluginJobStore>.Instance,
+            new DistributedCachePluginJobStoreOptions { WriteSettleDelay = TimeSpan.Zero });
+
+    private static PluginJobProcessor CreateProcessor(
+        IPluginExecutor executor,
+        IPluginJobStore jobStore,
+        PluginExecutorOptions? options = null) =>
+        new(executor, jobStore, new ServiceCollection().BuildServiceProvider(),
+            NullLogger<PluginJobProcessor>.Instance, options);
+
+    private static PluginJobState Job(string jobId) => new()
+    {
+        JobId = jobId,
+        PluginName = "hello-plugin",
+        FunctionName = "migrate_users",
+        UserId = "user-1",
+        JourneyId = "journey-1",
+        StepId = "migrate",
+        Input = new Dictionary<string, object> { ["batch_size"] = 1000 },
+        Config = new Dictionary<string, object> { ["batch_size"] = 1000 },
+        Checkpoint = "{\"offset\":2000}"
+    };
+}
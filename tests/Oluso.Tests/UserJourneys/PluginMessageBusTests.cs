@@ -0,0 +1,61 @@
+using FluentAssertions;
+using Microsoft.Extensions.DependencyInjection;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for the journey message bus as used by managed plugins in custom plugin steps
+/// </summary>
+public class PluginMessageBusTests : PluginStepTestBase
+{
+    private readonly InMemoryPluginMessageBus _messageBus = new();
+
+    public PluginMessageBusTests() : base("risk", "risk")
+    {
+    }
+
+    [Fact]
+    public async Task ManagedPlugin_ReceivesMessagesOnSubscribedTopics()
+    {
+        await _messageBus.PublishAsync(JourneyId, new PluginMessage { Topic = "risk.score", Payload = 70, Publisher = "collector" });
+        await _messageBus.PublishAsync(JourneyId, new PluginMessage { Topic = "device.trusted", Payload = true, Publisher = "collector" });
+
+        SetupPluginResult(new PluginExecutionResult { Success = true }, "decide_mfa");
+
+        await ExecuteStepAsync(CreateStepContext("decide_mfa", subscribe: new List<string> { "risk.score" }));
+
+        SeenByPlugin!.Messages.Should().ContainKey("risk.score").And.NotContainKey("device.trusted");
+        SeenByPlugin.Messages!["risk.score"].Should().ContainSingle().Which.Payload.Should().Be(70);
+    }
+
+    [Fact]
+    public async Task ManagedPlugin_PublishesReturnedMessages()
+    {
+        SetupPluginResult(new PluginExecutionResult
+        {
+            Success = true,
+            PublishedMessages = new[] { new PluginMessage { Topic = "risk.score", Payload = 70 } }
+        }, "collect_risk");
+
+        await ExecuteStepAsync(CreateStepContext("collect_risk"));
+
+        var messages = await _messageBus.GetMessagesAsync(JourneyId, new[] { "risk.score" });
+        messages["risk.score"].Should().ContainSingle().Which.Publisher.Should().Be("risk");
+    }
+
+    private StepExecutionContext CreateStepContext(string entryPoint, List<string>? subscribe = null)
+    {
+        var settings = new Dictionary<string, object> { ["entryPoint"] = entryPoint };
+        if (subscribe != null)
+        {
+            settings["subscribe"] = subscribe;
+        }
+
+        return CreateContext(
+            settings: settings,
+            services: CreateServices(s => s.AddSingleton<IPluginMessageBus>(_messageBus)));
+    }
+}
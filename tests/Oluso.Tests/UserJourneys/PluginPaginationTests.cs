@@ -0,0 +1,120 @@
+using FluentAssertions;
+using Microsoft.Extensions.DependencyInjection;
+using Moq;
+using Oluso.Core.UserJourneys;
+using Oluso.Tests.UserJourneys.Fixtures;
+using Xunit;
+
+namespace Oluso.Tests.UserJourneys;
+
+/// <summary>
+/// Tests for paginated plugin functions walked by the executor
+/// </summary>
+public class PluginPaginationTests : PluginStepTestBase
+{
+    private readonly List<PluginExecutionContext> _pageContexts = new();
+
+    public PluginPaginationTests() : base("directory", "list")
+    {
+    }
+
+    [Fact]
+    public async Task ExecuteAllPagesAsync_WalksEveryPage()
+    {
+        SetupPages(("0", new object[] { "user-1", "user-2" }, "2"),
+                   ("2", new object[] { "user-3", "user-4" }, "4"),
+                   ("4", new object[] { "user-5" }, null));
+
+        var result = await CreateExecutor().ExecuteAllPagesAsync("directory", "list_entries", new PluginExecutionContext
+        {
+            PageToken = "0",
+            IdempotencyKey = "journey-1:list:0",
+            SensitiveInputFields = new[] { "otp" }
+        });
+
+        result.Success.Should().BeTrue();
+        result.NextPageToken.Should().BeNull();
+        result.Output!["items"].Should().BeAssignableTo<IEnumerable<object>>()
+            .Which.Should().Equal("user-1", "user-2", "user-3", "user-4", "user-5");
+
+        _pageContexts.Select(c => c.PageToken).Should().Equal("0", "2", "4");
+        _pageContexts.Select(c => c.IdempotencyKey).Should().OnlyHaveUniqueItems();
+        _pageContexts.Should().OnlyContain(c => c.SensitiveInputFields!.Contains("otp"));
+    }
+
+    [Fact]
+    public async Task ExecuteAllPagesAsync_WithRepeatedPageToken_Fails()
+    {
+        SetupPages(("0", new object[] { "user-1" }, "0"));
+
+        var result = await CreateExecutor().ExecuteAllPagesAsync("directory", "list_entries",
+            new PluginExecutionContext { PageToken = "0" });
+
+        result.Success.Should().BeFalse();
+        result.Error.Should().Be("Plugin returned a repeated page token");
+    }
+
+    [Fact]
+    public async Task ExecuteAllPagesAsync_WithPageTokenCycle_FailsWithoutDuplicateItems()
+    {
+        SetupPages(("a", new object[] { "user-1" }, "b"),
+                   ("b", new object[] { "user-2" }, "a"));
+
+        var result = await CreateExecutor().ExecuteAllPagesAsync("directory", "list_entries",
+            new PluginExecutionContext { PageToken = "a" });
+
+        result.Success.Should().BeFalse();
+        result.Error.Should().Be("Plugin returned a repeated page token");
+        _pageContexts.Select(c => c.PageToken).Should().Equal("a", "b");
+    }
+
+    [Fact]
+    public async Task ExecuteAllPagesAsync_BeyondMaxPages_Fails()
+    {
+        SetupPages(("0", new object[] { "user-1" }, "1"),
+                   ("1", new object[] { "user-2" }, "2"),
+                   ("2", new object[] { "user-3" }, null));
+
+        var result = await CreateExecutor(new PluginExecutorOptions { MaxPages = 2 })
+            .ExecuteAllPagesAsync("directory", "list_entries", new PluginExecutionContext { PageToken = "0" });
+
+        result.Success.Should().BeFalse();
+        result.Error.Should().Be("Plugin returned more than 2 pages");
+    }
+
+    [Fact]
+    public async Task StepWithAllPages_OutputsItemsOfEveryPage()
+    {
+        SetupPages((null, new object[] { "user-1", "user-2" }, "2"),
+                   ("2", new object[] { "user-3" }, null));
+
+        var result = await ExecuteStepAsync(CreateContext(
+            settings: new Dictionary<string, object> { ["entryPoint"] = "list_entries", ["allPages"] = true },
+            services: CreateServices(s => s.AddSingleton<IPluginExecutor>(CreateExecutor()))));
+
+        result.Outcome.Should().Be(StepOutcome.Continue);
+        result.OutputData!["items"].Should().BeAssignableTo<IEnumerable<object>>()
+            .Which.Should().Equal("user-1", "user-2", "user-3");
+        _pageContexts.Should().HaveCount(2);
+    }
+
+    /// <summary>
+    /// Serves one page per page token; a null next token marks the last page
+    /// </summary>
+    private void SetupPages(params (string? Token, object[] Items, string? Next)[] pages)
+    {
+        PluginMock
+            .Setup(p => p.ExecuteAsync("list_entries", It.IsAny<PluginExecutionContext>(), It.IsAny<CancellationToken>()))
+            .ReturnsAsync((string _, PluginExecutionContext context, CancellationToken _) =>
+            {
+                _pageContexts.Add(context);
+                var page = pages.Single(p => p.Token == context.PageToken);
+                return new PluginExecutionResult
+                {
+                    Success = true,
+                    Output = new Dictionary<string, object> { ["items"] = page.Items.ToList() },
+                    NextPageToken = page.Next
+                };
+            });
+    }
+}